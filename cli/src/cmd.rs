@@ -1,6 +1,8 @@
 use clap::{Parser, Subcommand};
 use color_eyre::Result;
-use ethereum_types::{H160, U256};
+use ethereum_types::{H160, H256, U256};
+use evm_core::{chain::ChainSpec, spec::SpecId};
+use std::path::Path;
 
 #[derive(Parser, Debug)]
 #[clap(version, about, long_about = None)]
@@ -32,9 +34,74 @@ pub enum Commands {
         #[clap(short = 'C', long)]
         #[clap(default_value = "0x0000000000000000000000000000000000000000")]
         contract_address: H160,
+
+        #[clap(short, long)]
+        #[clap(default_value = "30000000")]
+        gas_limit: u64,
+
+        #[clap(long)]
+        #[clap(default_value = "1")]
+        chain_id: u32,
+
+        /// hardfork whose rules are followed, eg. shanghai or tangerine-whistle
+        #[clap(long)]
+        #[clap(default_value = "cancun")]
+        hardfork: SpecId,
+
+        /// follow the forks of mainnet, sepolia, holesky or of a JSON chain spec file
+        /// instead of a single hardfork, the chain id is taken from it as well
+        #[clap(long, conflicts_with_all = ["hardfork", "chain_id"])]
+        chain: Option<String>,
+
+        #[clap(long)]
+        #[clap(default_value = "0x0000000000000000000000000000000000000000")]
+        coinbase: H160,
+
+        #[clap(long)]
+        #[clap(default_value = "0")]
+        timestamp: u128,
+
+        #[clap(long)]
+        #[clap(default_value = "0")]
+        block_number: u128,
+
+        #[clap(long)]
+        #[clap(default_value = "30000000")]
+        block_gas_limit: u128,
+
+        #[clap(long)]
+        #[clap(default_value = "0")]
+        base_fee: U256,
+
+        #[clap(long)]
+        #[clap(default_value = "0")]
+        difficulty: U256,
+
+        #[clap(long)]
+        #[clap(
+            default_value = "0x0000000000000000000000000000000000000000000000000000000000000000"
+        )]
+        prevrandao: H256,
+
+        /// hashes of the previous blocks, the parent's first
+        #[clap(long, value_delimiter = ',')]
+        block_hashes: Vec<H256>,
+
+        /// write an EIP-3155 trace to this file, or to stdout instead of the report
+        #[clap(long, value_name = "FILE", num_args = 0..=1, default_missing_value = "-")]
+        trace_json: Option<String>,
     },
 }
 
+/// A chain spec file if `chain` is a path, one of the presets otherwise
+pub fn load_chain_spec(chain: &str) -> Result<ChainSpec> {
+    if Path::new(chain).is_file() {
+        Ok(ChainSpec::from_file(chain)?)
+    } else {
+        Ok(ChainSpec::named(chain)?)
+    }
+}
+
 pub fn decode_hex_string(hex: &str) -> Result<Vec<u8>> {
     if let Some(hex_str) = hex.strip_prefix("0x") {
         Ok(hex::decode(hex_str)?)
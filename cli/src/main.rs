@@ -1,11 +1,20 @@
 mod cmd;
 
+use std::{
+    cell::RefCell,
+    fs::File,
+    io::{self, BufWriter, Write},
+    rc::Rc,
+};
+
 use clap::Parser;
-use cmd::{decode_hex_string, App, Commands};
+use cmd::{decode_hex_string, load_chain_spec, App, Commands};
 use color_eyre::Result;
 use evm_core::{
+    environment::CurrentBlockInformation,
     evm::{Evm, EvmConfig},
     executor::ExecutionEnvironment,
+    tracer::JsonTracer,
 };
 
 fn main() -> Result<()> {
@@ -20,25 +29,77 @@ fn main() -> Result<()> {
             calldata,
             bytecode,
             contract_address,
+            gas_limit,
+            chain_id,
+            hardfork,
+            chain,
+            coinbase,
+            timestamp,
+            block_number,
+            block_gas_limit,
+            base_fee,
+            difficulty,
+            prevrandao,
+            block_hashes,
+            trace_json,
         } => {
             let program = decode_hex_string(&bytecode)?;
 
             let config = EvmConfig {
+                chain_id,
+                spec_id: hardfork,
+                chain_spec: chain.as_deref().map(load_chain_spec).transpose()?,
+                current_block: CurrentBlockInformation {
+                    timestamp,
+                    gas_limit: block_gas_limit,
+                    block_number,
+                    coinbase,
+                    base_fee,
+                    difficulty,
+                    prevrandao,
+                    block_hashes,
+                    ..Default::default()
+                },
                 root_execution_env: ExecutionEnvironment {
                     value,
                     caller,
                     calldata: decode_hex_string(&calldata)?,
                     contract_address,
+                    gas_limit,
+                    ..Default::default()
                 },
-                ..Default::default()
             };
 
             let mut evm = Evm::new_with_config(config);
+
+            let tracer = match trace_json.as_deref() {
+                None => None,
+                Some(path) => {
+                    let output: Box<dyn Write> = match path {
+                        "-" => Box::new(io::stdout().lock()),
+                        path => Box::new(BufWriter::new(File::create(path)?)),
+                    };
+                    let tracer = Rc::new(RefCell::new(JsonTracer::new(output)));
+                    evm.set_inspector(tracer.clone());
+                    Some(tracer)
+                }
+            };
+
             let mut executor = evm.build_executor();
-            let result = executor.run(program)?;
+            let result = executor.run(program);
+
+            if let Some(tracer) = tracer {
+                tracer.borrow_mut().summary(&result, evm.spec_id())?;
+
+                // keep stdout to the trace lines
+                if trace_json.as_deref() == Some("-") {
+                    return Ok(());
+                }
+            }
 
             println!("\n{result}");
             print!("{}", executor.execution_machine);
+            print!("\n{}", result.access_set);
         }
     }
 
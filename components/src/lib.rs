@@ -8,6 +8,8 @@ use memory::Memory;
 use program_counter::ProgramCounter;
 use stack::Stack;
 
+pub use stack::StackError;
+
 #[derive(Debug)]
 pub struct ExecutionMachine {
     pub stack: Stack,
@@ -14,23 +14,24 @@ impl Memory {
     ///
     /// it needs to be mutable because reading to a bigger offset will trigger a memory expansion https://www.evm.codes/about#memoryexpansion
     pub fn read_bytes(&mut self, offset: usize, total: usize) -> Vec<u8> {
-        let size = self.0.len();
-        let to = offset + total;
-
-        if to > size {
-            self.0.resize_with(to, || 0);
+        if total == 0 {
+            return Vec::new();
         }
 
+        let to = offset + total;
+        self.expand(to);
         self.0[offset..to].to_vec()
     }
 
     /// if the size of `value` + `offset` is bigger than the current memory size,
     /// then expand memory first before putting in the value.
     pub fn write_bytes(&mut self, offset: usize, value: Vec<u8>) {
-        let to = offset + value.len();
-        if to > self.0.len() {
-            self.0.resize_with(to, || 0);
+        if value.is_empty() {
+            return;
         }
+
+        let to = offset + value.len();
+        self.expand(to);
         self.0.splice(offset..to, value);
     }
 
@@ -38,6 +39,18 @@ impl Memory {
     pub fn used_capacity(&self) -> usize {
         self.0.len()
     }
+
+    /// Returns the amount of memory used in 32-byte words
+    pub fn size_in_words(&self) -> usize {
+        self.0.len().div_ceil(32)
+    }
+
+    /// memory is always expanded by a whole word
+    fn expand(&mut self, to: usize) {
+        if to > self.0.len() {
+            self.0.resize_with(to.div_ceil(32) * 32, || 0);
+        }
+    }
 }
 
 impl std::fmt::Display for Memory {
@@ -66,7 +79,8 @@ mod tests {
         let value: Vec<u8> = vec![4, 2, 0];
         let mut memory = Memory(vec![6, 9]);
         memory.write_bytes(1, value);
-        assert_eq!(memory.0, &[6, 4, 2, 0]);
+        assert_eq!(memory.0.len(), 32);
+        assert_eq!(memory.0[..5], [6, 4, 2, 0, 0]);
     }
 
     #[test]
@@ -89,4 +103,12 @@ mod tests {
         let mut memory = Memory(vec![6; 128]);
         assert_eq!(memory.read_bytes(0, 32).len(), 32);
     }
+
+    #[test]
+    fn zero_sized_access_does_not_expand() {
+        let mut memory = Memory::new();
+        assert!(memory.read_bytes(1024, 0).is_empty());
+        memory.write_bytes(1024, vec![]);
+        assert_eq!(memory.used_capacity(), 0);
+    }
 }
@@ -8,14 +8,17 @@ impl ProgramCounter {
         Self::default()
     }
 
+    #[inline]
     pub fn increment_by(&mut self, value: usize) {
         self.0 += value;
     }
 
+    #[inline]
     pub fn set_exact(&mut self, value: usize) {
         self.0 = value;
     }
 
+    #[inline]
     pub fn get(&self) -> usize {
         self.0
     }
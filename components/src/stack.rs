@@ -21,16 +21,18 @@ pub struct Stack {
 }
 
 impl Stack {
+    /// A stack that never reallocates, its whole capacity is allocated upfront
     pub fn new(max_capacity: usize) -> Self {
         Self {
             max_capacity,
-            inner: Vec::new(),
+            inner: Vec::with_capacity(max_capacity),
         }
     }
 
     /// `offset` the offset amount from the top of the stack
+    #[inline]
     pub fn set_from_top(&mut self, offset: usize, value: U256) -> Result<U256> {
-        let index = (self.height() - 1) - offset;
+        let index = self.index_from_top(offset)?;
         let pos = self
             .inner
             .get_mut(index)
@@ -41,15 +43,26 @@ impl Stack {
     }
 
     /// `offset` the offset amount from the top of the stack
+    #[inline]
     pub fn get_from_top(&self, offset: usize) -> Result<U256> {
-        let index = (self.height() - 1) - offset;
+        let index = self.index_from_top(offset)?;
         self.inner
             .get(index)
             .ok_or(StackError::IndexOutOfBounds)
             .copied()
     }
 
+    #[inline]
+    fn index_from_top(&self, offset: usize) -> Result<usize> {
+        self.inner
+            .len()
+            .checked_sub(offset)
+            .and_then(|len| len.checked_sub(1))
+            .ok_or(StackError::IndexOutOfBounds)
+    }
+
     // return error if already at max capacity
+    #[inline]
     pub fn push(&mut self, value: U256) -> Result<()> {
         if self.inner.len() == self.max_capacity {
             return Err(StackError::StackOverflow);
@@ -58,10 +71,26 @@ impl Stack {
         Ok(())
     }
 
+    #[inline]
     pub fn pop(&mut self) -> Result<U256> {
         self.inner.pop().ok_or(StackError::StackUnderflow)
     }
 
+    /// Checks that `pops` items can be popped and `pushes` pushed afterwards
+    #[inline]
+    pub fn require(&self, pops: usize, pushes: usize) -> Result<()> {
+        if self.inner.len() < pops {
+            return Err(StackError::StackUnderflow);
+        }
+
+        if self.inner.len() - pops + pushes > self.max_capacity {
+            return Err(StackError::StackOverflow);
+        }
+
+        Ok(())
+    }
+
+    #[inline]
     pub fn height(&self) -> usize {
         self.inner.len()
     }
@@ -73,6 +102,11 @@ impl Stack {
     pub fn is_empty(&self) -> bool {
         self.inner.is_empty()
     }
+
+    /// Every item, from the bottom to the top
+    pub fn items(&self) -> &[U256] {
+        &self.inner
+    }
 }
 
 impl std::fmt::Display for Stack {
@@ -125,4 +159,33 @@ mod tests {
         let value = stack.get_from_top(1).ok();
         assert_eq!(value, Some(U256::from(128)))
     }
+
+    #[test]
+    fn access_past_the_bottom_fails() {
+        let mut stack = Stack::new(10);
+        assert!(stack.get_from_top(0).is_err());
+        assert!(stack.set_from_top(0, U256::one()).is_err());
+
+        stack.push(U256::from(1)).unwrap();
+        assert!(stack.get_from_top(1).is_err());
+        assert!(stack.get_from_top(usize::MAX).is_err());
+    }
+
+    #[test]
+    fn require_checks_both_bounds() {
+        let mut stack = Stack::new(3);
+        stack.push(U256::from(1)).unwrap();
+        stack.push(U256::from(2)).unwrap();
+
+        assert!(stack.require(2, 1).is_ok());
+        assert!(stack.require(1, 2).is_ok());
+        assert!(matches!(
+            stack.require(3, 0),
+            Err(StackError::StackUnderflow)
+        ));
+        assert!(matches!(
+            stack.require(0, 2),
+            Err(StackError::StackOverflow)
+        ));
+    }
 }
@@ -0,0 +1,36 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use evm_core::{bytecode::Bytecode, executor::ExecutionContext};
+
+fn decode(code: &str) -> Bytecode {
+    (0..code.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&code[i..i + 2], 16).unwrap())
+        .collect::<Vec<u8>>()
+        .into()
+}
+
+fn run(code: &Bytecode) {
+    let result = ExecutionContext::default().run(code.clone());
+    assert!(result.is_success());
+}
+
+fn loops(c: &mut Criterion) {
+    // counts down from 0xffff: JUMPDEST PUSH1 1 SWAP1 SUB DUP1 PUSH1 3 JUMPI
+    let countdown = decode("61ffff5b6001900380600357");
+
+    // same loop, also storing i * i + i in memory and loading it back
+    let memory = decode("612fff5b80800280016000526000515060019003806003576000");
+
+    // hashes the counter every iteration
+    let hashing = decode("610fff5b806000526020600020506001900380600357");
+
+    // only the cost of setting up a context
+    let empty = decode("00");
+    c.bench_function("empty", |b| b.iter(|| run(black_box(&empty))));
+    c.bench_function("countdown", |b| b.iter(|| run(black_box(&countdown))));
+    c.bench_function("memory", |b| b.iter(|| run(black_box(&memory))));
+    c.bench_function("hashing", |b| b.iter(|| run(black_box(&hashing))));
+}
+
+criterion_group!(benches, loops);
+criterion_main!(benches);
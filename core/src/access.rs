@@ -0,0 +1,144 @@
+use std::collections::BTreeSet;
+
+use color_eyre::owo_colors::OwoColorize;
+use ethereum_types::{H160, U256};
+
+/// Accounts and storage slots declared by a transaction as it will access them,
+/// see EIP-2930
+pub type AccessList = Vec<(H160, Vec<U256>)>;
+
+/// Accounts and storage slots accessed during a transaction, see EIP-2929
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AccessSet {
+    addresses: BTreeSet<H160>,
+    storage_keys: BTreeSet<(H160, U256)>,
+}
+
+impl AccessSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The set every transaction starts with: the origin, the target, all the
+    /// precompiles and everything in the access list are already warm
+    pub fn for_transaction<'a>(
+        origin: H160,
+        target: H160,
+        precompiles: impl IntoIterator<Item = &'a H160>,
+        access_list: &AccessList,
+    ) -> Self {
+        let mut access_set = Self::new();
+
+        access_set.access_address(origin);
+        access_set.access_address(target);
+
+        for address in precompiles {
+            access_set.access_address(*address);
+        }
+
+        for (address, keys) in access_list {
+            access_set.access_address(*address);
+            for key in keys {
+                access_set.access_storage_key(*address, *key);
+            }
+        }
+
+        access_set
+    }
+
+    /// Marks `address` as warm, returns true if it was cold before
+    pub fn access_address(&mut self, address: H160) -> bool {
+        self.addresses.insert(address)
+    }
+
+    /// Marks the slot `key` of `address` as warm, returns true if it was cold before
+    pub fn access_storage_key(&mut self, address: H160, key: U256) -> bool {
+        self.storage_keys.insert((address, key))
+    }
+
+    /// Makes `address` cold again, used when the frame that warmed it reverts
+    pub fn remove_address(&mut self, address: &H160) {
+        self.addresses.remove(address);
+    }
+
+    /// Makes the slot `key` of `address` cold again
+    pub fn remove_storage_key(&mut self, address: H160, key: U256) {
+        self.storage_keys.remove(&(address, key));
+    }
+
+    pub fn contains_address(&self, address: &H160) -> bool {
+        self.addresses.contains(address)
+    }
+
+    pub fn contains_storage_key(&self, address: H160, key: U256) -> bool {
+        self.storage_keys.contains(&(address, key))
+    }
+
+    pub fn addresses(&self) -> impl Iterator<Item = &H160> {
+        self.addresses.iter()
+    }
+
+    pub fn storage_keys(&self) -> impl Iterator<Item = &(H160, U256)> {
+        self.storage_keys.iter()
+    }
+}
+
+impl std::fmt::Display for AccessSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut items = String::new();
+
+        for address in &self.addresses {
+            items.push_str(&format!("{address:?}\n"));
+
+            for (_, key) in self.storage_keys.iter().filter(|(a, _)| a == address) {
+                items.push_str(&format!("  {key:#066x}\n"));
+            }
+        }
+
+        write!(
+            f,
+            r"{}
+{}",
+            "[ Accessed ]".purple(),
+            items
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transaction_access_set_is_pre_warmed() {
+        let origin = H160::from_low_u64_be(0xaa);
+        let target = H160::from_low_u64_be(0xbb);
+        let precompiles = [H160::from_low_u64_be(0x01), H160::from_low_u64_be(0x0a)];
+        let listed = H160::from_low_u64_be(0xcc);
+        let access_list = vec![(listed, vec![U256::one()])];
+        let access_set = AccessSet::for_transaction(origin, target, &precompiles, &access_list);
+
+        assert!(access_set.contains_address(&origin));
+        assert!(access_set.contains_address(&target));
+        assert!(access_set.contains_address(&H160::from_low_u64_be(0x01)));
+        assert!(access_set.contains_address(&H160::from_low_u64_be(0x0a)));
+        assert!(!access_set.contains_address(&H160::from_low_u64_be(0x0b)));
+        assert!(access_set.contains_address(&listed));
+        assert!(access_set.contains_storage_key(listed, U256::one()));
+        assert!(!access_set.contains_storage_key(listed, U256::zero()));
+    }
+
+    #[test]
+    fn access_reports_cold_only_once() {
+        let mut access_set = AccessSet::new();
+        let address = H160::from_low_u64_be(0x69);
+
+        assert!(access_set.access_address(address));
+        assert!(!access_set.access_address(address));
+
+        assert!(access_set.access_storage_key(address, U256::one()));
+        assert!(!access_set.access_storage_key(address, U256::one()));
+        assert!(access_set.access_storage_key(address, U256::zero()));
+        assert!(!access_set.contains_storage_key(H160::zero(), U256::one()));
+    }
+}
@@ -0,0 +1,111 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use ethereum_types::H256;
+
+const JUMPDEST: u8 = 0x5B;
+const PUSH1: u8 = 0x60;
+const PUSH32: u8 = 0x7F;
+
+/// The valid jump destinations of a piece of code: JUMPDEST bytes that are not
+/// part of the immediate data of a PUSH
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct JumpTable {
+    bitmap: Vec<u8>,
+}
+
+impl JumpTable {
+    pub fn analyze(code: &[u8]) -> Self {
+        let mut bitmap = vec![0u8; code.len().div_ceil(8)];
+        let mut offset = 0;
+
+        while let Some(&opcode) = code.get(offset) {
+            if opcode == JUMPDEST {
+                bitmap[offset / 8] |= 1 << (offset % 8);
+            }
+
+            offset += match opcode {
+                PUSH1..=PUSH32 => (opcode - PUSH1) as usize + 2,
+                _ => 1,
+            };
+        }
+
+        Self { bitmap }
+    }
+
+    pub fn is_valid(&self, offset: usize) -> bool {
+        self.bitmap
+            .get(offset / 8)
+            .is_some_and(|byte| byte & (1 << (offset % 8)) != 0)
+    }
+}
+
+/// Jump tables by code hash, so that identical code is analyzed only once. The hash
+/// is the one stored with the code, it is never computed here.
+#[derive(Debug, Default, Clone)]
+pub struct JumpTableCache {
+    tables: Rc<RefCell<HashMap<H256, Rc<JumpTable>>>>,
+}
+
+impl JumpTableCache {
+    /// The jump table of `code`, whose keccak256 is `hash`
+    pub fn get_or_analyze(&self, hash: H256, code: &[u8]) -> Rc<JumpTable> {
+        self.tables
+            .borrow_mut()
+            .entry(hash)
+            .or_insert_with(|| Rc::new(JumpTable::analyze(code)))
+            .clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.tables.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tables.borrow().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_data_is_not_a_jumpdest() {
+        // JUMPDEST PUSH2 0x5b5b JUMPDEST PUSH1
+        let table = JumpTable::analyze(&[0x5b, 0x61, 0x5b, 0x5b, 0x5b, 0x60]);
+
+        assert!(table.is_valid(0));
+        assert!(!table.is_valid(1));
+        assert!(!table.is_valid(2));
+        assert!(!table.is_valid(3));
+        assert!(table.is_valid(4));
+        assert!(!table.is_valid(5));
+        assert!(!table.is_valid(6));
+        assert!(!table.is_valid(usize::MAX));
+    }
+
+    #[test]
+    fn truncated_push_covers_the_end_of_code() {
+        let mut code = vec![0x7f];
+        code.extend([0x5b; 20]);
+
+        let table = JumpTable::analyze(&code);
+
+        assert!((0..code.len()).all(|offset| !table.is_valid(offset)));
+        assert!(!JumpTable::analyze(&[]).is_valid(0));
+    }
+
+    #[test]
+    fn cache_analyzes_code_once() {
+        let cache = JumpTableCache::default();
+        let hash = H256::repeat_byte(1);
+
+        let first = cache.get_or_analyze(hash, &[0x5b, 0x00]);
+        let second = cache.clone().get_or_analyze(hash, &[0x5b, 0x00]);
+        cache.get_or_analyze(H256::repeat_byte(2), &[0x00, 0x5b]);
+
+        assert!(Rc::ptr_eq(&first, &second));
+        assert!(first.is_valid(0));
+        assert_eq!(cache.len(), 2);
+    }
+}
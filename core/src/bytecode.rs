@@ -0,0 +1,183 @@
+use std::rc::Rc;
+
+use ethereum_types::{H256, U256};
+
+use crate::analysis::{JumpTable, JumpTableCache};
+use crate::utils::{copy_padded, crypto::keccak256};
+
+/// Zeros after the code, enough for a PUSH32 at the very end followed by a STOP
+const PADDING: usize = 33;
+
+/// Code ready to be executed, hashed and analyzed once. Cloning only bumps a
+/// reference count so it can be passed around freely between accounts and frames.
+#[derive(Debug, Clone)]
+pub struct Bytecode(Rc<AnalyzedCode>);
+
+#[derive(Debug)]
+struct AnalyzedCode {
+    padded: Vec<u8>,
+    len: usize,
+    hash: H256,
+    jump_table: Rc<JumpTable>,
+}
+
+impl Bytecode {
+    pub fn new(code: Vec<u8>) -> Self {
+        let hash = keccak256(&code);
+        let jump_table = Rc::new(JumpTable::analyze(&code));
+
+        Self::from_parts(code, hash, jump_table)
+    }
+
+    /// Like `new`, but reuses the analysis of identical code found in `cache`
+    pub fn new_cached(code: Vec<u8>, cache: &JumpTableCache) -> Self {
+        let hash = keccak256(&code);
+        let jump_table = cache.get_or_analyze(hash, &code);
+
+        Self::from_parts(code, hash, jump_table)
+    }
+
+    fn from_parts(code: Vec<u8>, hash: H256, jump_table: Rc<JumpTable>) -> Self {
+        let len = code.len();
+        let mut padded = code;
+        padded.resize(len + PADDING, 0);
+
+        Self(Rc::new(AnalyzedCode {
+            padded,
+            len,
+            hash,
+            jump_table,
+        }))
+    }
+
+    /// The code without its padding
+    pub fn bytes(&self) -> &[u8] {
+        &self.0.padded[..self.0.len]
+    }
+
+    /// The code followed by zeros, so that reading the immediate of a truncated
+    /// PUSH never goes out of bounds
+    pub fn padded(&self) -> &[u8] {
+        &self.0.padded
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.len == 0
+    }
+
+    pub fn hash(&self) -> H256 {
+        self.0.hash
+    }
+
+    pub fn jump_table(&self) -> &JumpTable {
+        &self.0.jump_table
+    }
+
+    /// `size` bytes of code from `offset`, zero padded past the end, as read by
+    /// CODECOPY and EXTCODECOPY
+    pub fn copy(&self, offset: U256, size: usize) -> Vec<u8> {
+        copy_padded(self.bytes(), offset, size)
+    }
+}
+
+impl Default for Bytecode {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl PartialEq for Bytecode {
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes() == other.bytes()
+    }
+}
+
+impl Eq for Bytecode {}
+
+impl From<Vec<u8>> for Bytecode {
+    fn from(code: Vec<u8>) -> Self {
+        Self::new(code)
+    }
+}
+
+impl From<&[u8]> for Bytecode {
+    fn from(code: &[u8]) -> Self {
+        Self::new(code.to_vec())
+    }
+}
+
+/// Code given to an `ExecutionContext`, raw code is analyzed through the cache
+/// shared by the transaction
+pub trait IntoBytecode {
+    fn into_bytecode(self, cache: &JumpTableCache) -> Bytecode;
+}
+
+impl IntoBytecode for Bytecode {
+    fn into_bytecode(self, _: &JumpTableCache) -> Bytecode {
+        self
+    }
+}
+
+impl IntoBytecode for Vec<u8> {
+    fn into_bytecode(self, cache: &JumpTableCache) -> Bytecode {
+        Bytecode::new_cached(self, cache)
+    }
+}
+
+impl IntoBytecode for &[u8] {
+    fn into_bytecode(self, cache: &JumpTableCache) -> Bytecode {
+        Bytecode::new_cached(self.to_vec(), cache)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::crypto::KECCAK_EMPTY;
+
+    #[test]
+    fn code_is_padded() {
+        // PUSH32 with only two bytes of immediate
+        let code = Bytecode::new(vec![0x7f, 0x01, 0x02]);
+
+        assert_eq!(code.len(), 3);
+        assert_eq!(code.bytes(), &[0x7f, 0x01, 0x02]);
+        assert_eq!(code.padded().len(), 3 + PADDING);
+        assert!(code.padded()[3..].iter().all(|byte| *byte == 0));
+        assert_eq!(Bytecode::default().hash(), KECCAK_EMPTY);
+    }
+
+    #[test]
+    fn copy_is_zero_padded() {
+        let code = Bytecode::new(vec![0x01, 0x02, 0x03]);
+
+        assert_eq!(code.copy(U256::zero(), 2), vec![0x01, 0x02]);
+        assert_eq!(code.copy(U256::one(), 4), vec![0x02, 0x03, 0x00, 0x00]);
+        assert_eq!(code.copy(U256::from(3), 2), vec![0x00, 0x00]);
+        assert_eq!(code.copy(U256::MAX, 1), vec![0x00]);
+    }
+
+    #[test]
+    fn clones_share_the_analysis() {
+        let code = Bytecode::new(vec![0x5b, 0x00]);
+        let clone = code.clone();
+
+        assert!(std::ptr::eq(code.jump_table(), clone.jump_table()));
+        assert!(clone.jump_table().is_valid(0));
+        assert_eq!(code, Bytecode::from(&[0x5b, 0x00][..]));
+    }
+
+    #[test]
+    fn identical_code_shares_the_cached_analysis() {
+        let cache = JumpTableCache::default();
+        let first = Bytecode::new_cached(vec![0x5b, 0x00], &cache);
+        let second = vec![0x5b, 0x00].into_bytecode(&cache);
+
+        assert!(std::ptr::eq(first.jump_table(), second.jump_table()));
+        assert_eq!(cache.len(), 1);
+    }
+}
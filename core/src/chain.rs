@@ -0,0 +1,252 @@
+use std::{collections::BTreeMap, path::Path};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::environment::CurrentBlockInformation;
+use crate::spec::SpecId;
+
+#[derive(Debug, Error)]
+pub enum ChainSpecError {
+    #[error("unknown chain : {0}")]
+    UnknownChain(String),
+    #[error("failed to read chain spec : {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid chain spec : {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// When a hardfork activates, forks after the Merge activate at a timestamp
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ForkCondition {
+    Block(u64),
+    Timestamp(u64),
+}
+
+impl ForkCondition {
+    pub fn is_active(&self, block: &CurrentBlockInformation) -> bool {
+        match *self {
+            ForkCondition::Block(number) => block.block_number >= number as u128,
+            ForkCondition::Timestamp(timestamp) => block.timestamp >= timestamp as u128,
+        }
+    }
+}
+
+/// A chain and the activation of each of its hardforks, eg.
+///
+/// ```json
+/// {
+///     "name": "devnet",
+///     "chain_id": 1337,
+///     "forks": {
+///         "london": { "block": 0 },
+///         "shanghai": { "timestamp": 1700000000 }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ChainSpec {
+    pub name: String,
+    pub chain_id: u32,
+    pub forks: BTreeMap<SpecId, ForkCondition>,
+}
+
+impl ChainSpec {
+    pub fn mainnet() -> Self {
+        use ForkCondition::*;
+
+        Self {
+            name: "mainnet".to_string(),
+            chain_id: 1,
+            forks: BTreeMap::from([
+                (SpecId::Frontier, Block(0)),
+                (SpecId::Homestead, Block(1_150_000)),
+                (SpecId::TangerineWhistle, Block(2_463_000)),
+                (SpecId::SpuriousDragon, Block(2_675_000)),
+                (SpecId::Byzantium, Block(4_370_000)),
+                // Constantinople activated together with Petersburg, which removed EIP-1283
+                (SpecId::Constantinople, Block(7_280_000)),
+                (SpecId::Petersburg, Block(7_280_000)),
+                (SpecId::Istanbul, Block(9_069_000)),
+                (SpecId::Berlin, Block(12_244_000)),
+                (SpecId::London, Block(12_965_000)),
+                (SpecId::Merge, Block(15_537_394)),
+                (SpecId::Shanghai, Timestamp(1_681_338_455)),
+                (SpecId::Cancun, Timestamp(1_710_338_135)),
+            ]),
+        }
+    }
+
+    pub fn sepolia() -> Self {
+        use ForkCondition::*;
+
+        Self {
+            name: "sepolia".to_string(),
+            chain_id: 11_155_111,
+            forks: BTreeMap::from([
+                (SpecId::London, Block(0)),
+                (SpecId::Merge, Block(1_735_371)),
+                (SpecId::Shanghai, Timestamp(1_677_557_088)),
+                (SpecId::Cancun, Timestamp(1_706_655_072)),
+            ]),
+        }
+    }
+
+    pub fn holesky() -> Self {
+        use ForkCondition::*;
+
+        Self {
+            name: "holesky".to_string(),
+            chain_id: 17000,
+            forks: BTreeMap::from([
+                (SpecId::Merge, Block(0)),
+                (SpecId::Shanghai, Timestamp(1_696_000_704)),
+                (SpecId::Cancun, Timestamp(1_707_305_664)),
+            ]),
+        }
+    }
+
+    /// A chain whose forks are all active from genesis up to `spec`
+    pub fn custom(name: &str, chain_id: u32, spec: SpecId) -> Self {
+        Self {
+            name: name.to_string(),
+            chain_id,
+            forks: BTreeMap::from([(spec, ForkCondition::Block(0))]),
+        }
+    }
+
+    /// One of the presets by name
+    pub fn named(name: &str) -> Result<Self, ChainSpecError> {
+        match name.to_lowercase().as_str() {
+            "mainnet" => Ok(Self::mainnet()),
+            "sepolia" => Ok(Self::sepolia()),
+            "holesky" => Ok(Self::holesky()),
+            _ => Err(ChainSpecError::UnknownChain(name.to_string())),
+        }
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, ChainSpecError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ChainSpecError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// The latest hardfork active at `block`, or Frontier if none is. Forks missing
+    /// from the spec activate with the next one.
+    pub fn spec_at(&self, block: &CurrentBlockInformation) -> SpecId {
+        self.forks
+            .iter()
+            .rev()
+            .find(|(_, condition)| condition.is_active(block))
+            .map_or(SpecId::Frontier, |(spec, _)| *spec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(block_number: u128, timestamp: u128) -> CurrentBlockInformation {
+        CurrentBlockInformation {
+            block_number,
+            timestamp,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn mainnet_forks_by_block_number() {
+        let mainnet = ChainSpec::mainnet();
+
+        assert_eq!(mainnet.spec_at(&block(0, 0)), SpecId::Frontier);
+        assert_eq!(mainnet.spec_at(&block(1_149_999, 0)), SpecId::Frontier);
+        assert_eq!(mainnet.spec_at(&block(1_150_000, 0)), SpecId::Homestead);
+        assert_eq!(mainnet.spec_at(&block(7_280_000, 0)), SpecId::Petersburg);
+        assert_eq!(mainnet.spec_at(&block(12_965_000, 0)), SpecId::London);
+        assert_eq!(
+            mainnet.spec_at(&block(15_537_394, 1_663_224_162)),
+            SpecId::Merge
+        );
+    }
+
+    #[test]
+    fn mainnet_forks_by_timestamp() {
+        let mainnet = ChainSpec::mainnet();
+
+        assert_eq!(
+            mainnet.spec_at(&block(17_034_870, 1_681_338_455)),
+            SpecId::Shanghai
+        );
+        assert_eq!(
+            mainnet.spec_at(&block(19_426_586, 1_710_338_134)),
+            SpecId::Shanghai
+        );
+        assert_eq!(
+            mainnet.spec_at(&block(19_426_587, 1_710_338_135)),
+            SpecId::Cancun
+        );
+        // Prague is not implemented, Cancun is the latest fork
+        assert_eq!(
+            mainnet.spec_at(&block(22_431_084, 1_746_612_311)),
+            SpecId::Cancun
+        );
+    }
+
+    #[test]
+    fn testnets_start_after_frontier() {
+        assert_eq!(ChainSpec::sepolia().spec_at(&block(0, 0)), SpecId::London);
+        assert_eq!(
+            ChainSpec::sepolia().spec_at(&block(5_187_023, 1_706_655_072)),
+            SpecId::Cancun
+        );
+        assert_eq!(ChainSpec::holesky().spec_at(&block(0, 0)), SpecId::Merge);
+        assert_eq!(ChainSpec::named("Holesky").unwrap().chain_id, 17000);
+        assert!(ChainSpec::named("ropsten").is_err());
+    }
+
+    #[test]
+    fn custom_chain_spec_from_json() {
+        let chain = ChainSpec::from_json(
+            r#"{
+                "name": "devnet",
+                "chain_id": 1337,
+                "forks": {
+                    "london": { "block": 0 },
+                    "merge": { "block": 10 },
+                    "shanghai": { "timestamp": 1000 },
+                    "cancun": { "timestamp": 2000 }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(chain.name, "devnet");
+        assert_eq!(chain.chain_id, 1337);
+        assert_eq!(chain.spec_at(&block(9, 999)), SpecId::London);
+        assert_eq!(chain.spec_at(&block(10, 999)), SpecId::Merge);
+        assert_eq!(chain.spec_at(&block(11, 1500)), SpecId::Shanghai);
+        assert_eq!(chain.spec_at(&block(12, 2000)), SpecId::Cancun);
+
+        assert_eq!(
+            ChainSpec::custom("devnet", 1337, SpecId::Berlin).spec_at(&block(0, 0)),
+            SpecId::Berlin
+        );
+    }
+
+    #[test]
+    fn invalid_chain_spec() {
+        assert!(ChainSpec::from_json(r#"{ "name": "devnet" }"#).is_err());
+        assert!(ChainSpec::from_json(
+            r#"{ "name": "devnet", "chain_id": 1, "forks": { "dao": { "block": 0 } } }"#
+        )
+        .is_err());
+        assert!(ChainSpec::from_json(
+            r#"{ "name": "devnet", "chain_id": 1, "forks": { "london": { "epoch": 0 } } }"#
+        )
+        .is_err());
+        assert!(ChainSpec::from_file("/does/not/exist.json").is_err());
+    }
+}
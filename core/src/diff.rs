@@ -0,0 +1,258 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use ethereum_types::{H160, U256};
+
+use crate::bytecode::Bytecode;
+use crate::environment::GlobalStorage;
+use crate::journal::JournalEntry;
+
+/// A value before and after the transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diff<T> {
+    pub from: T,
+    pub to: T,
+}
+
+impl<T: Clone> Diff<T> {
+    fn unchanged(value: T) -> Self {
+        Self {
+            from: value.clone(),
+            to: value,
+        }
+    }
+}
+
+/// What a transaction changed in one account, only the fields that differ are set
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AccountDiff {
+    pub balance: Option<Diff<U256>>,
+    pub nonce: Option<Diff<u64>>,
+    pub code: Option<Diff<Bytecode>>,
+    pub storage: BTreeMap<U256, Diff<U256>>,
+    /// whether the account was deleted by SELFDESTRUCT
+    pub destroyed: bool,
+}
+
+impl AccountDiff {
+    pub fn is_empty(&self) -> bool {
+        self.balance.is_none()
+            && self.nonce.is_none()
+            && self.code.is_none()
+            && self.storage.is_empty()
+            && !self.destroyed
+    }
+}
+
+/// The accounts changed by a transaction
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StateDiff(pub BTreeMap<H160, AccountDiff>);
+
+impl StateDiff {
+    /// The values that the state had before the changes recorded in `entries`
+    pub fn from_journal(entries: &[JournalEntry]) -> Self {
+        let mut accounts = BTreeMap::<H160, AccountDiff>::new();
+
+        // the first change of a value holds what it was before the transaction
+        for entry in entries {
+            match entry {
+                JournalEntry::AccountCreated { address }
+                | JournalEntry::ContractCreated { address } => {
+                    accounts.entry(*address).or_default();
+                }
+
+                JournalEntry::BalanceChanged { address, previous } => {
+                    let account = accounts.entry(*address).or_default();
+                    account.balance.get_or_insert(Diff::unchanged(*previous));
+                }
+
+                JournalEntry::NonceChanged { address, previous } => {
+                    let account = accounts.entry(*address).or_default();
+                    account.nonce.get_or_insert(Diff::unchanged(*previous));
+                }
+
+                JournalEntry::CodeChanged { address, previous } => {
+                    let account = accounts.entry(*address).or_default();
+                    account.code.get_or_insert_with(|| {
+                        Diff::unchanged(previous.clone().unwrap_or_default())
+                    });
+                }
+
+                JournalEntry::StorageChanged {
+                    address,
+                    key,
+                    previous,
+                } => {
+                    let account = accounts.entry(*address).or_default();
+                    account
+                        .storage
+                        .entry(*key)
+                        .or_insert(Diff::unchanged(*previous));
+                }
+
+                JournalEntry::SelfDestructed { address } => {
+                    accounts.entry(*address).or_default();
+                }
+
+                JournalEntry::TransientStorageChanged { .. }
+                | JournalEntry::AddressWarmed { .. }
+                | JournalEntry::StorageKeyWarmed { .. }
+                | JournalEntry::LogPushed => {}
+            }
+        }
+
+        Self(accounts)
+    }
+
+    /// Fills in the values of `state` after the transaction, dropping whatever ended up unchanged
+    pub fn finish(mut self, state: &GlobalStorage, destroyed: &BTreeSet<H160>) -> Self {
+        for (address, diff) in self.0.iter_mut() {
+            let account = state.get(address);
+
+            if let Some(balance) = &mut diff.balance {
+                balance.to = account.map(|account| account.balance).unwrap_or_default();
+            }
+
+            if let Some(nonce) = &mut diff.nonce {
+                nonce.to = account.map(|account| account.nonce).unwrap_or_default();
+            }
+
+            if let Some(code) = &mut diff.code {
+                code.to = account
+                    .and_then(|account| account.code.clone())
+                    .unwrap_or_default();
+            }
+
+            for (key, value) in diff.storage.iter_mut() {
+                value.to = account
+                    .and_then(|account| account.storage.get(key).copied())
+                    .unwrap_or_default();
+            }
+
+            diff.balance = diff.balance.take().filter(|diff| diff.from != diff.to);
+            diff.nonce = diff.nonce.take().filter(|diff| diff.from != diff.to);
+            diff.code = diff.code.take().filter(|diff| diff.from != diff.to);
+            diff.storage.retain(|_, diff| diff.from != diff.to);
+            diff.destroyed = destroyed.contains(address);
+        }
+
+        self.0.retain(|_, diff| !diff.is_empty());
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn get(&self, address: &H160) -> Option<&AccountDiff> {
+        self.0.get(address)
+    }
+}
+
+impl std::fmt::Display for StateDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (address, diff) in &self.0 {
+            writeln!(
+                f,
+                "{address:?}{}",
+                if diff.destroyed { " (destroyed)" } else { "" }
+            )?;
+
+            if let Some(balance) = &diff.balance {
+                writeln!(f, "  balance: {} -> {}", balance.from, balance.to)?;
+            }
+
+            if let Some(nonce) = &diff.nonce {
+                writeln!(f, "  nonce: {} -> {}", nonce.from, nonce.to)?;
+            }
+
+            if let Some(code) = &diff.code {
+                writeln!(f, "  code: {} -> {} bytes", code.from.len(), code.to.len())?;
+            }
+
+            for (key, value) in &diff.storage {
+                writeln!(
+                    f,
+                    "  storage[{key:#x}]: {:#x} -> {:#x}",
+                    value.from, value.to
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::environment::AccountState;
+
+    #[test]
+    fn keeps_first_previous_value_and_drops_unchanged() {
+        let address = H160::repeat_byte(1);
+        let entries = [
+            JournalEntry::BalanceChanged {
+                address,
+                previous: U256::from(10),
+            },
+            JournalEntry::BalanceChanged {
+                address,
+                previous: U256::from(7),
+            },
+            JournalEntry::StorageChanged {
+                address,
+                key: U256::one(),
+                previous: U256::zero(),
+            },
+            // set and then reset
+            JournalEntry::NonceChanged {
+                address,
+                previous: 1,
+            },
+        ];
+
+        let mut state = GlobalStorage::new();
+        state.insert(
+            address,
+            AccountState {
+                nonce: 1,
+                balance: U256::from(3),
+                storage: BTreeMap::from([(U256::one(), U256::from(5))]),
+                ..Default::default()
+            },
+        );
+
+        let diff = StateDiff::from_journal(&entries).finish(&state, &BTreeSet::new());
+        let account = diff.get(&address).unwrap();
+
+        assert_eq!(
+            account.balance,
+            Some(Diff {
+                from: U256::from(10),
+                to: U256::from(3)
+            })
+        );
+        assert_eq!(account.nonce, None);
+        assert_eq!(
+            account.storage.get(&U256::one()),
+            Some(&Diff {
+                from: U256::zero(),
+                to: U256::from(5)
+            })
+        );
+    }
+
+    #[test]
+    fn destroyed_accounts_are_reset() {
+        let address = H160::repeat_byte(2);
+        let entries = [JournalEntry::SelfDestructed { address }];
+
+        let diff = StateDiff::from_journal(&entries)
+            .finish(&GlobalStorage::new(), &BTreeSet::from([address]));
+
+        assert!(diff.get(&address).unwrap().destroyed);
+        assert!(StateDiff::from_journal(&[JournalEntry::LogPushed])
+            .finish(&GlobalStorage::new(), &BTreeSet::new())
+            .is_empty());
+    }
+}
@@ -3,47 +3,165 @@ use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
 use color_eyre::owo_colors::OwoColorize;
 use ethereum_types::{H160, H256, U256};
 
+use crate::access::AccessSet;
+use crate::analysis::JumpTableCache;
+use crate::bytecode::Bytecode;
+use crate::diff::StateDiff;
+use crate::halt::HaltReason;
+use crate::log::Log;
+use crate::spec::SpecId;
+
 #[derive(Debug, Default)]
 pub struct AccountState {
+    pub nonce: u64,
     pub balance: U256,
-    pub code: Option<Vec<u8>>,
+    pub code: Option<Bytecode>,
     pub storage: BTreeMap<U256, U256>,
 }
 
+impl AccountState {
+    /// An account with no nonce, no balance and no code, see EIP-161
+    pub fn is_empty(&self) -> bool {
+        self.nonce == 0
+            && self.balance.is_zero()
+            && self.code.as_ref().is_none_or(|code| code.is_empty())
+    }
+}
+
 pub type GlobalStorage = BTreeMap<H160, AccountState>;
 
+/// Number of previous blocks whose hash is available to BLOCKHASH
+pub const BLOCK_HASH_HISTORY: usize = 256;
+
 #[derive(Debug, Default, Clone)]
 pub struct CurrentBlockInformation {
     pub timestamp: u128,
     pub gas_limit: u128,
     pub block_hash: H256,
     pub block_number: u128,
+    pub coinbase: H160,
+    /// see EIP-1559
+    pub base_fee: U256,
+    /// what PREVRANDAO returns before the merge
+    pub difficulty: U256,
+    /// randomness from the beacon chain, see EIP-4399
+    pub prevrandao: H256,
+    /// hashes of the previous blocks, the parent's first, only the
+    /// first `BLOCK_HASH_HISTORY` are used
+    pub block_hashes: Vec<H256>,
+}
+
+impl CurrentBlockInformation {
+    /// Hash of the block `number`, if it is one of the `BLOCK_HASH_HISTORY` previous blocks
+    pub fn block_hash(&self, number: U256) -> Option<H256> {
+        let current = U256::from(self.block_number);
+
+        if number >= current || current - number > U256::from(BLOCK_HASH_HISTORY) {
+            return None;
+        }
+
+        let age = (current - number).as_usize();
+        self.block_hashes.get(age - 1).copied()
+    }
 }
 
 #[derive(Debug, Default)]
 pub struct GlobalEnvironment {
     pub chain_id: u32,
+    pub spec_id: SpecId,
     pub current_block: CurrentBlockInformation,
     pub global_storage: Rc<RefCell<GlobalStorage>>,
+    pub jump_tables: JumpTableCache,
 }
 
+/// How a transaction ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionStatus {
+    Success,
+    /// ended with REVERT, its changes are discarded but the gas left is not consumed
+    Revert,
+    /// stopped exceptionally, its changes are discarded and all the gas is consumed
+    Halt(HaltReason),
+}
+
+#[derive(Debug)]
 pub struct ExecutionResult {
+    pub status: ExecutionStatus,
+    /// returned data, or the revert data
     pub data: Vec<u8>,
+    /// gas paid for the transaction, once the refund is deducted
+    pub gas_used: u64,
+    pub gas_refunded: u64,
+    /// events emitted by the frames that succeeded
+    pub logs: Vec<Log>,
+    /// address of the contract deployed by a successful `ExecutionContext::deploy`
+    pub created_address: Option<H160>,
+    /// what the transaction changed, empty unless it succeeded
+    pub state_diff: StateDiff,
+    /// accounts and storage slots touched by the transaction
+    pub access_set: AccessSet,
+}
+
+impl ExecutionResult {
+    pub fn is_success(&self) -> bool {
+        self.status == ExecutionStatus::Success
+    }
+
+    pub fn is_revert(&self) -> bool {
+        self.status == ExecutionStatus::Revert
+    }
+
+    pub fn is_halt(&self) -> bool {
+        matches!(self.status, ExecutionStatus::Halt(_))
+    }
+
+    pub fn halt_reason(&self) -> Option<HaltReason> {
+        match self.status {
+            ExecutionStatus::Halt(reason) => Some(reason),
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for ExecutionResult {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(
+        let status = match self.status {
+            ExecutionStatus::Success => "[ Output ]".to_string(),
+            ExecutionStatus::Revert => "[ Reverted ]".to_string(),
+            ExecutionStatus::Halt(reason) => format!("[ Halted : {reason} ]"),
+        };
+
+        writeln!(f, "{}", status.purple())?;
+        writeln!(
             f,
-            r"{}
-0x{}
-",
-            "[ Output ]".purple(),
+            "0x{}",
             self.data
                 .iter()
                 .map(|i| format!("{i:02x}"))
                 .collect::<Vec<String>>()
                 .join("")
-        )
+        )?;
+        writeln!(
+            f,
+            "\ngas used: {}\ngas refunded: {}",
+            self.gas_used, self.gas_refunded
+        )?;
+
+        if let Some(address) = self.created_address {
+            writeln!(f, "created: {address:?}")?;
+        }
+
+        if !self.logs.is_empty() {
+            write!(f, "\n{}\n", "[ Logs ]".purple())?;
+            for log in &self.logs {
+                write!(f, "{log}")?;
+            }
+        }
+
+        if !self.state_diff.is_empty() {
+            write!(f, "\n{}\n{}", "[ State changes ]".purple(), self.state_diff)?;
+        }
+
+        Ok(())
     }
 }
@@ -1,39 +1,87 @@
 use std::{cell::RefCell, rc::Rc};
 
+use crate::chain::ChainSpec;
 use crate::environment::{CurrentBlockInformation, GlobalEnvironment, GlobalStorage};
 use crate::executor::{ExecutionContext, ExecutionEnvironment};
+use crate::inspector::SharedInspector;
+use crate::spec::SpecId;
 
 #[derive(Debug, Default, Clone)]
 pub struct EvmConfig {
     pub chain_id: u32,
+    /// hardfork whose rules are followed
+    pub spec_id: SpecId,
+    /// when given, the chain id and the hardfork active at the current block are taken from it
+    pub chain_spec: Option<ChainSpec>,
     pub current_block: CurrentBlockInformation,
     pub root_execution_env: ExecutionEnvironment,
 }
 
+impl EvmConfig {
+    pub fn chain_id(&self) -> u32 {
+        self.chain_spec
+            .as_ref()
+            .map_or(self.chain_id, |chain| chain.chain_id)
+    }
+
+    pub fn spec_id(&self) -> SpecId {
+        self.chain_spec
+            .as_ref()
+            .map_or(self.spec_id, |chain| chain.spec_at(&self.current_block))
+    }
+}
+
 #[derive(Default)]
 pub struct Evm {
     config: EvmConfig,
     global_env: Rc<GlobalEnvironment>,
+    /// given to every executor built from now on
+    inspector: Option<SharedInspector>,
 }
 
 // TODO: its ugly and doesnt make sense pls fix
 impl Evm {
     pub fn new_with_config(config: EvmConfig) -> Self {
         Self {
-            config: config.clone(),
             global_env: Rc::new(GlobalEnvironment {
-                chain_id: config.chain_id,
-                current_block: config.current_block,
+                chain_id: config.chain_id(),
+                spec_id: config.spec_id(),
+                current_block: config.current_block.clone(),
                 global_storage: Rc::new(RefCell::new(GlobalStorage::default())),
+                jump_tables: Default::default(),
             }),
+            config,
+            inspector: None,
         }
     }
 
+    /// Moves on to `block` keeping the state, the rules may change if it crosses a fork
+    pub fn set_block(&mut self, block: CurrentBlockInformation) {
+        self.config.current_block = block;
+        self.global_env = Rc::new(GlobalEnvironment {
+            chain_id: self.config.chain_id(),
+            spec_id: self.config.spec_id(),
+            current_block: self.config.current_block.clone(),
+            global_storage: self.global_env.global_storage.clone(),
+            jump_tables: self.global_env.jump_tables.clone(),
+        });
+    }
+
+    pub fn spec_id(&self) -> SpecId {
+        self.global_env.spec_id
+    }
+
+    pub fn set_inspector(&mut self, inspector: SharedInspector) {
+        self.inspector = Some(inspector);
+    }
+
     pub fn build_executor(&mut self) -> ExecutionContext {
-        ExecutionContext::new(
+        let mut executor = ExecutionContext::new(
             self.config.root_execution_env.clone(),
             self.global_env.clone(),
-        )
+        );
+        executor.inspector = self.inspector.clone();
+        executor
     }
 }
 
@@ -49,9 +97,9 @@ mod tests {
         });
 
         let mut exec = evm.build_executor();
-        let result = exec.run(program).unwrap();
+        let result = exec.run(program);
 
-        assert_eq!(result.data, vec![]);
+        assert_eq!(result.data, Vec::<u8>::new());
     }
 
     #[test]
@@ -65,7 +113,7 @@ mod tests {
         });
 
         let mut exec = evm.build_executor();
-        let result = exec.run(program).unwrap();
+        let result = exec.run(program);
 
         let mut expected_value = vec![0u8; 32];
         expected_value[30] = 32;
@@ -74,4 +122,59 @@ mod tests {
         assert_eq!(result.data.len(), 32);
         assert_eq!(result.data, expected_value);
     }
+
+    #[test]
+    fn rules_follow_the_chain_spec() {
+        let mut evm = Evm::new_with_config(EvmConfig {
+            chain_id: 5,
+            spec_id: SpecId::Frontier,
+            chain_spec: Some(ChainSpec::mainnet()),
+            current_block: CurrentBlockInformation {
+                block_number: 19_426_586,
+                timestamp: 1_710_338_123,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+
+        assert_eq!(evm.spec_id(), SpecId::Shanghai);
+        assert_eq!(evm.build_executor().global_env.chain_id, 1);
+
+        // TLOAD(0) is only valid after the fork
+        assert!(evm.build_executor().run(vec![0x60, 0x00, 0x5C]).is_halt());
+
+        evm.set_block(CurrentBlockInformation {
+            block_number: 19_426_587,
+            timestamp: 1_710_338_135,
+            ..Default::default()
+        });
+
+        assert_eq!(evm.spec_id(), SpecId::Cancun);
+        assert!(evm
+            .build_executor()
+            .run(vec![0x60, 0x00, 0x5C])
+            .is_success());
+    }
+
+    #[test]
+    fn state_is_kept_across_blocks() {
+        let mut evm = Evm::new_with_config(EvmConfig::default());
+        let storage = evm.build_executor().global_env.global_storage.clone();
+
+        // SSTORE(0, 1)
+        assert!(evm
+            .build_executor()
+            .run(vec![0x60, 0x01, 0x60, 0x00, 0x55])
+            .is_success());
+
+        evm.set_block(CurrentBlockInformation {
+            block_number: 1,
+            ..Default::default()
+        });
+
+        let executor = evm.build_executor();
+        assert!(Rc::ptr_eq(&executor.global_env.global_storage, &storage));
+        assert_eq!(executor.global_env.current_block.block_number, 1);
+        assert_eq!(storage.borrow().len(), 1);
+    }
 }
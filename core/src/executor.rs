@@ -1,456 +1,1118 @@
-use crate::environment::{ExecutionResult, GlobalEnvironment};
-use crate::operation::{OpCode, OperationError};
-use crate::utils::convert_u256_to_eth_address;
-use crate::{construct_dup_op, construct_log_op, construct_push_op, construct_swap_op};
+use crate::access::{AccessList, AccessSet};
+use crate::bytecode::{Bytecode, IntoBytecode};
+use crate::diff::StateDiff;
+use crate::environment::{
+    AccountState, ExecutionResult, ExecutionStatus, GlobalEnvironment, GlobalStorage,
+};
+use crate::gas::{self, Gas, GasError};
+use crate::halt::HaltReason;
+use crate::inspector::{FrameEnd, SharedInspector};
+use crate::journal::{Checkpoint, Journal, JournalEntry};
+use crate::log::Log;
+use crate::operation::{OpCode, OPCODES};
+use crate::precompile::Precompiles;
+use crate::spec::SpecId;
+use crate::utils::{
+    convert_u256_to_eth_address,
+    crypto::{create2_address, create_address},
+};
+
+mod instructions;
+
+use instructions::{Status, INSTRUCTIONS};
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+    rc::Rc,
+};
+
+use ethereum_types::{H160, H256, U256};
+use evm_components::ExecutionMachine;
 
-use std::{cell::RefCell, rc::Rc};
+type Result<T> = std::result::Result<T, HaltReason>;
 
-use color_eyre::{eyre, eyre::bail, Result};
-use ethereum_types::{H160, U256};
-use evm_components::ExecutionMachine;
-use sha3::{Digest, Sha3_256};
+/// Maximum depth of nested call frames
+pub const MAX_CALL_DEPTH: usize = 1024;
+/// Maximum size of a contract's code, see EIP-170
+pub const MAX_CODE_SIZE: usize = 24576;
+/// Maximum size of init code, see EIP-3860
+pub const MAX_INITCODE_SIZE: usize = 2 * MAX_CODE_SIZE;
+/// Maximum memory of a frame, it costs about 2 billion gas so only a gas limit given
+/// by hand reaches it, reaching it is running out of gas
+pub const MAX_MEMORY_SIZE: u64 = 32 * 1024 * 1024;
+
+// call frames are executed recursively, so the native stack is grown on demand
+// to not overflow before reaching `MAX_CALL_DEPTH`
+const STACK_RED_ZONE: usize = 128 * 1024;
+const STACK_GROW_SIZE: usize = 4 * 1024 * 1024;
 
 // this should be something that user can set through the cli
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Clone)]
 pub struct ExecutionEnvironment {
     pub value: U256,
     pub caller: H160,
     pub calldata: Vec<u8>,
     pub contract_address: H160,
-    // gas_count: u128,
+    pub gas_limit: u64,
+    /// whether state modifications are disallowed, see EIP-214
+    pub is_static: bool,
+}
+
+impl Default for ExecutionEnvironment {
+    fn default() -> Self {
+        Self {
+            value: U256::zero(),
+            caller: H160::zero(),
+            calldata: Vec::new(),
+            contract_address: H160::zero(),
+            gas_limit: gas::DEFAULT_GAS_LIMIT,
+            is_static: false,
+        }
+    }
+}
+
+/// How a call frame ended, when it did not halt exceptionally
+enum FrameOutcome {
+    Return(Vec<u8>),
+    Revert(Vec<u8>),
 }
 
-#[derive(Default)]
 pub struct ExecutionContext {
-    // TODO: change logs format
-    pub logs: Rc<RefCell<Vec<Vec<U256>>>>,
+    /// changes made to the state during the transaction, so that they can be reverted
+    pub journal: Rc<RefCell<Journal>>,
+    /// events emitted during the transaction, removed with the frame that emitted them
+    /// and moved to the result at its end
+    pub logs: Rc<RefCell<Vec<Log>>>,
+    /// value of every written storage slot at the start of the transaction
+    pub original_storage: Rc<RefCell<BTreeMap<(H160, U256), U256>>>,
+    /// accounts and slots accessed during the transaction, rebuilt when it starts
+    pub access_set: Rc<RefCell<AccessSet>>,
+    /// accounts and slots warm from the start of the transaction, see EIP-2930
+    pub access_list: AccessList,
+    /// transient storage of EIP-1153, discarded at the end of the transaction
+    pub transient_storage: Rc<RefCell<BTreeMap<(H160, U256), U256>>>,
+    /// contracts created during the transaction, see EIP-6780
+    pub created_contracts: Rc<RefCell<BTreeSet<H160>>>,
+    /// accounts deleted by SELFDESTRUCT at the end of the transaction
+    pub selfdestructs: Rc<RefCell<BTreeSet<H160>>>,
     pub global_env: Rc<GlobalEnvironment>,
+    /// contracts implemented natively at the transaction's hardfork
+    pub precompiles: Rc<Precompiles>,
     pub execution_env: ExecutionEnvironment,
     pub execution_machine: ExecutionMachine,
+    /// output of the last call or create made by this frame, see EIP-211
+    pub return_data: Vec<u8>,
+    pub gas: Gas,
+    /// 0 for the transaction's own frame, incremented for every nested call
+    pub depth: usize,
+    /// observes the execution of every frame of the transaction
+    pub inspector: Option<SharedInspector>,
+    /// data of the RETURN or REVERT that ended the frame
+    output: Vec<u8>,
+}
+
+impl Default for ExecutionContext {
+    fn default() -> Self {
+        Self::new(ExecutionEnvironment::default(), Rc::default())
+    }
 }
 
 impl ExecutionContext {
     pub fn new(execution_env: ExecutionEnvironment, global_env: Rc<GlobalEnvironment>) -> Self {
         Self {
+            precompiles: Rc::new(Precompiles::new(global_env.spec_id)),
             global_env,
+            gas: Gas::new(execution_env.gas_limit),
             execution_env,
+            journal: Rc::new(RefCell::new(Journal::new())),
             logs: Rc::new(RefCell::new(Vec::new())),
+            original_storage: Rc::new(RefCell::new(BTreeMap::new())),
+            access_set: Rc::new(RefCell::new(AccessSet::new())),
+            access_list: AccessList::new(),
+            transient_storage: Rc::new(RefCell::new(BTreeMap::new())),
+            created_contracts: Rc::new(RefCell::new(BTreeSet::new())),
+            selfdestructs: Rc::new(RefCell::new(BTreeSet::new())),
             execution_machine: ExecutionMachine::default(),
+            return_data: Vec::new(),
+            depth: 0,
+            inspector: None,
+            output: Vec::new(),
         }
     }
 
-    pub fn run(&mut self, program: Vec<u8>) -> Result<ExecutionResult> {
-        let mut result = Vec::new();
+    /// A context for a nested call frame, sharing everything that lives for the whole transaction
+    fn new_child(&self, execution_env: ExecutionEnvironment) -> Self {
+        Self {
+            journal: self.journal.clone(),
+            logs: self.logs.clone(),
+            original_storage: self.original_storage.clone(),
+            access_set: self.access_set.clone(),
+            access_list: AccessList::new(),
+            transient_storage: self.transient_storage.clone(),
+            created_contracts: self.created_contracts.clone(),
+            selfdestructs: self.selfdestructs.clone(),
+            global_env: self.global_env.clone(),
+            precompiles: self.precompiles.clone(),
+            gas: Gas::new(execution_env.gas_limit),
+            execution_env,
+            execution_machine: ExecutionMachine::default(),
+            return_data: Vec::new(),
+            depth: self.depth + 1,
+            inspector: self.inspector.clone(),
+            output: Vec::new(),
+        }
+    }
 
-        while let Some(opcode) = program.get(self.execution_machine.pc.get()) {
-            let operation = OpCode::from(*opcode);
+    pub fn with_inspector(mut self, inspector: SharedInspector) -> Self {
+        self.inspector = Some(inspector);
+        self
+    }
 
-            match operation {
-                OpCode::STOP => return Ok(ExecutionResult { data: result }),
+    pub fn with_access_list(mut self, access_list: AccessList) -> Self {
+        self.access_list = access_list;
+        self
+    }
 
-                OpCode::ADD => {
-                    let a = self.execution_machine.stack.pop()?;
-                    let b = self.execution_machine.stack.pop()?;
-                    let (res, _) = a.overflowing_add(b);
-                    self.execution_machine.stack.push(res)?;
-                    self.execution_machine.pc.increment_by(1);
-                }
+    /// Runs `code` as the code of the environment's contract
+    pub fn run(&mut self, code: impl IntoBytecode) -> ExecutionResult {
+        self.start_transaction();
+        let checkpoint = self.checkpoint();
+        let code = code.into_bytecode(&self.global_env.jump_tables);
+        let result = self.run_frame(&code);
 
-                OpCode::SUB => {
-                    let a = self.execution_machine.stack.pop()?;
-                    let b = self.execution_machine.stack.pop()?;
-                    let (res, _) = a.overflowing_sub(b);
-                    self.execution_machine.stack.push(res)?;
-                    self.execution_machine.pc.increment_by(1);
-                }
+        self.finish(checkpoint, result, None)
+    }
 
-                OpCode::MUL => {
-                    let a = self.execution_machine.stack.pop()?;
-                    let b = self.execution_machine.stack.pop()?;
-                    let (res, _) = a.overflowing_mul(b);
-                    self.execution_machine.stack.push(res)?;
-                    self.execution_machine.pc.increment_by(1);
-                }
+    /// Runs `init_code` as a contract creation, the code it returns is deployed at
+    /// the environment's contract address
+    pub fn deploy(&mut self, init_code: impl IntoBytecode) -> ExecutionResult {
+        self.start_transaction();
+        let checkpoint = self.checkpoint();
+        let address = self.execution_env.contract_address;
+        self.start_contract(address);
+
+        let init_code = init_code.into_bytecode(&self.global_env.jump_tables);
+        let result = match self.run_frame(&init_code) {
+            Ok(FrameOutcome::Return(code)) => self
+                .deposit_code(code)
+                .map(|()| FrameOutcome::Return(Vec::new())),
+            outcome => outcome,
+        };
+
+        if result.is_err() {
+            self.gas.consume_all();
+        }
 
-                // technically we wont be accepting non-hex values, so...
-                OpCode::DIV | OpCode::SDIV => {
-                    let a = self.execution_machine.stack.pop()?;
-                    let b = self.execution_machine.stack.pop()?;
-                    let res = a.checked_div(b).unwrap_or(U256::zero());
-                    self.execution_machine.stack.push(res)?;
-                    self.execution_machine.pc.increment_by(1);
-                }
+        self.finish(checkpoint, result, Some(address))
+    }
 
-                // technically we wont be accepting non-hex values, so...
-                OpCode::MOD | OpCode::SMOD => {
-                    let a = self.execution_machine.stack.pop()?;
-                    let b = self.execution_machine.stack.pop()?;
-                    self.execution_machine.stack.push(a % b)?;
-                    self.execution_machine.pc.increment_by(1);
-                }
+    /// A context can run several transactions, each one starts with a new machine, all of its
+    /// gas and only the accounts and slots it pre-warms
+    fn start_transaction(&mut self) {
+        self.execution_machine = ExecutionMachine::default();
+        self.return_data.clear();
+        self.output.clear();
+        self.gas = Gas::new(self.execution_env.gas_limit);
+        *self.access_set.borrow_mut() = self.transaction_access_set();
+    }
 
-                OpCode::ADDMOD => {
-                    let a = self.execution_machine.stack.pop()?;
-                    let b = self.execution_machine.stack.pop()?;
-                    let n = self.execution_machine.stack.pop()?;
+    /// The accounts and slots that are warm before the transaction runs any code
+    fn transaction_access_set(&self) -> AccessSet {
+        let mut access_set = AccessSet::for_transaction(
+            self.execution_env.caller,
+            self.execution_env.contract_address,
+            self.precompiles.addresses(),
+            &self.access_list,
+        );
+
+        // the coinbase is warm as well since EIP-3651
+        if self.global_env.spec_id.is_enabled(SpecId::Shanghai) {
+            access_set.access_address(self.global_env.current_block.coinbase);
+        }
 
-                    let (c, _) = a.overflowing_add(b);
-                    let value = c % n;
+        access_set
+    }
 
-                    self.execution_machine.stack.push(value)?;
-                    self.execution_machine.pc.increment_by(1);
-                }
+    /// Ends the transaction, keeping its changes only if it succeeded
+    fn finish(
+        &mut self,
+        checkpoint: Checkpoint,
+        result: Result<FrameOutcome>,
+        created_address: Option<H160>,
+    ) -> ExecutionResult {
+        let mut state_diff = StateDiff::default();
+
+        if let Ok(FrameOutcome::Return(_)) = result {
+            // the journal is emptied by the commit
+            let changes = StateDiff::from_journal(self.journal.borrow().entries());
+            self.commit(checkpoint);
+
+            let mut global_storage = self.global_env.global_storage.borrow_mut();
+            for address in self.selfdestructs.borrow().iter() {
+                global_storage.remove(address);
+            }
 
-                OpCode::MULMOD => {
-                    let a = self.execution_machine.stack.pop()?;
-                    let b = self.execution_machine.stack.pop()?;
-                    let n = self.execution_machine.stack.pop()?;
+            state_diff = changes.finish(&global_storage, &self.selfdestructs.borrow());
+        } else {
+            self.revert_to(checkpoint);
+            self.gas.clear_refund();
+        }
 
-                    let (c, _) = a.overflowing_mul(b);
-                    let value = c % n;
+        // nothing of the transaction is carried over to the next one
+        self.journal.borrow_mut().clear();
+        self.original_storage.borrow_mut().clear();
+        self.transient_storage.borrow_mut().clear();
+        self.created_contracts.borrow_mut().clear();
+        self.selfdestructs.borrow_mut().clear();
+        let logs = std::mem::take(&mut *self.logs.borrow_mut());
+
+        let (status, data) = match result {
+            Ok(FrameOutcome::Return(data)) => (ExecutionStatus::Success, data),
+            Ok(FrameOutcome::Revert(data)) => (ExecutionStatus::Revert, data),
+            Err(reason) => (ExecutionStatus::Halt(reason), Vec::new()),
+        };
+
+        let gas_refunded = self.gas.final_refund(self.global_env.spec_id);
+
+        ExecutionResult {
+            created_address: created_address.filter(|_| status == ExecutionStatus::Success),
+            status,
+            data,
+            gas_used: self.gas.used() - gas_refunded,
+            gas_refunded,
+            logs,
+            state_diff,
+            access_set: self.access_set.borrow().clone(),
+        }
+    }
 
-                    self.execution_machine.stack.push(value)?;
-                    self.execution_machine.pc.increment_by(1);
-                }
+    fn run_frame(&mut self, code: &Bytecode) -> Result<FrameOutcome> {
+        // nested calls never go this deep, only a context built by hand can
+        let result = if self.depth > MAX_CALL_DEPTH {
+            Err(HaltReason::CallDepthExceeded)
+        } else {
+            self.execute(code)
+        };
+
+        // any exceptional halt consumes all the gas given to the context
+        if result.is_err() {
+            self.gas.consume_all();
+        }
 
-                OpCode::EXP => {
-                    let a = self.execution_machine.stack.pop()?;
-                    let exponent = self.execution_machine.stack.pop()?;
-                    let (value, _) = a.overflowing_pow(exponent);
-                    self.execution_machine.stack.push(value)?;
-                    self.execution_machine.pc.increment_by(1);
-                }
+        result
+    }
+
+    fn balance_of(&self, address: H160) -> U256 {
+        self.global_env
+            .global_storage
+            .borrow()
+            .get(&address)
+            .map(|account| account.balance)
+            .unwrap_or_default()
+    }
+
+    fn code_of(&self, address: H160) -> Bytecode {
+        self.global_env
+            .global_storage
+            .borrow()
+            .get(&address)
+            .and_then(|account| account.code.clone())
+            .unwrap_or_default()
+    }
+
+    fn storage_of(&self, address: H160, key: U256) -> U256 {
+        self.global_env
+            .global_storage
+            .borrow()
+            .get(&address)
+            .and_then(|account| account.storage.get(&key).copied())
+            .unwrap_or_default()
+    }
+
+    /// Moves `value` between two accounts, the caller checks that `from` can afford it
+    fn transfer(&self, from: H160, to: H160, value: U256) {
+        self.set_balance(from, self.balance_of(from).saturating_sub(value));
+        self.set_balance(to, self.balance_of(to).saturating_add(value));
+    }
+
+    fn checkpoint(&self) -> Checkpoint {
+        self.journal.borrow_mut().checkpoint()
+    }
+
+    fn commit(&self, checkpoint: Checkpoint) {
+        self.journal.borrow_mut().commit(checkpoint);
+    }
 
-                OpCode::LT | OpCode::SLT => {
-                    let a = self.execution_machine.stack.pop()?;
-                    let b = self.execution_machine.stack.pop()?;
-                    let result = if a < b { U256::one() } else { U256::zero() };
+    /// Undoes every change made to the state since `checkpoint`
+    fn revert_to(&self, checkpoint: Checkpoint) {
+        let entries = self.journal.borrow_mut().revert(checkpoint);
+        let mut global_storage = self.global_env.global_storage.borrow_mut();
 
-                    self.execution_machine.stack.push(result)?;
-                    self.execution_machine.pc.increment_by(1);
+        for entry in entries {
+            match entry {
+                JournalEntry::AccountCreated { address } => {
+                    global_storage.remove(&address);
                 }
 
-                OpCode::GT | OpCode::SGT => {
-                    let a = self.execution_machine.stack.pop()?;
-                    let b = self.execution_machine.stack.pop()?;
-                    let result = if a > b { U256::one() } else { U256::zero() };
+                JournalEntry::BalanceChanged { address, previous } => {
+                    if let Some(account) = global_storage.get_mut(&address) {
+                        account.balance = previous;
+                    }
+                }
 
-                    self.execution_machine.stack.push(result)?;
-                    self.execution_machine.pc.increment_by(1);
+                JournalEntry::NonceChanged { address, previous } => {
+                    if let Some(account) = global_storage.get_mut(&address) {
+                        account.nonce = previous;
+                    }
                 }
 
-                OpCode::EQ => {
-                    let a = self.execution_machine.stack.pop()?;
-                    let b = self.execution_machine.stack.pop()?;
-                    let result = if a == b { U256::one() } else { U256::zero() };
+                JournalEntry::CodeChanged { address, previous } => {
+                    if let Some(account) = global_storage.get_mut(&address) {
+                        account.code = previous;
+                    }
+                }
 
-                    self.execution_machine.stack.push(result)?;
-                    self.execution_machine.pc.increment_by(1);
+                JournalEntry::StorageChanged {
+                    address,
+                    key,
+                    previous,
+                } => {
+                    if let Some(account) = global_storage.get_mut(&address) {
+                        if previous.is_zero() {
+                            account.storage.remove(&key);
+                        } else {
+                            account.storage.insert(key, previous);
+                        }
+                    }
                 }
 
-                OpCode::ISZERO => {
-                    let a = self.execution_machine.stack.pop()?;
-                    let result = if a.is_zero() {
-                        U256::one()
+                JournalEntry::TransientStorageChanged {
+                    address,
+                    key,
+                    previous,
+                } => {
+                    let mut transient_storage = self.transient_storage.borrow_mut();
+                    if previous.is_zero() {
+                        transient_storage.remove(&(address, key));
                     } else {
-                        U256::zero()
-                    };
+                        transient_storage.insert((address, key), previous);
+                    }
+                }
 
-                    self.execution_machine.stack.push(result)?;
-                    self.execution_machine.pc.increment_by(1);
+                JournalEntry::AddressWarmed { address } => {
+                    self.access_set.borrow_mut().remove_address(&address);
                 }
 
-                OpCode::AND => {
-                    let a = self.execution_machine.stack.pop()?;
-                    let b = self.execution_machine.stack.pop()?;
-                    let result = a & b;
+                JournalEntry::StorageKeyWarmed { address, key } => {
+                    self.access_set
+                        .borrow_mut()
+                        .remove_storage_key(address, key);
+                }
 
-                    self.execution_machine.stack.push(result)?;
-                    self.execution_machine.pc.increment_by(1);
+                JournalEntry::LogPushed => {
+                    self.logs.borrow_mut().pop();
                 }
 
-                OpCode::OR => {
-                    let a = self.execution_machine.stack.pop()?;
-                    let b = self.execution_machine.stack.pop()?;
-                    let result = a | b;
+                JournalEntry::ContractCreated { address } => {
+                    self.created_contracts.borrow_mut().remove(&address);
+                }
 
-                    self.execution_machine.stack.push(result)?;
-                    self.execution_machine.pc.increment_by(1);
+                JournalEntry::SelfDestructed { address } => {
+                    self.selfdestructs.borrow_mut().remove(&address);
                 }
+            }
+        }
+    }
 
-                OpCode::XOR => {
-                    let a = self.execution_machine.stack.pop()?;
-                    let b = self.execution_machine.stack.pop()?;
-                    let result = a ^ b;
+    /// The account at `address`, created if it does not exist yet
+    fn account_mut<'a>(
+        &self,
+        global_storage: &'a mut GlobalStorage,
+        address: H160,
+    ) -> &'a mut AccountState {
+        global_storage.entry(address).or_insert_with(|| {
+            self.journal
+                .borrow_mut()
+                .record(JournalEntry::AccountCreated { address });
+            AccountState::default()
+        })
+    }
 
-                    self.execution_machine.stack.push(result)?;
-                    self.execution_machine.pc.increment_by(1);
-                }
+    fn set_balance(&self, address: H160, balance: U256) {
+        let mut global_storage = self.global_env.global_storage.borrow_mut();
+        let account = self.account_mut(&mut global_storage, address);
+        let previous = std::mem::replace(&mut account.balance, balance);
 
-                OpCode::NOT => {
-                    let a = self.execution_machine.stack.pop()?;
-                    let result = !a;
+        self.journal
+            .borrow_mut()
+            .record(JournalEntry::BalanceChanged { address, previous });
+    }
 
-                    self.execution_machine.stack.push(result)?;
-                    self.execution_machine.pc.increment_by(1);
-                }
+    fn set_nonce(&self, address: H160, nonce: u64) {
+        let mut global_storage = self.global_env.global_storage.borrow_mut();
+        let account = self.account_mut(&mut global_storage, address);
+        let previous = std::mem::replace(&mut account.nonce, nonce);
 
-                OpCode::BYTE => {
-                    let i = self.execution_machine.stack.pop()?.as_usize();
-                    let x = self.execution_machine.stack.pop()?;
+        self.journal
+            .borrow_mut()
+            .record(JournalEntry::NonceChanged { address, previous });
+    }
 
-                    let mut bytes = Vec::new();
-                    x.to_big_endian(&mut bytes);
+    fn set_code(&self, address: H160, code: Bytecode) {
+        let mut global_storage = self.global_env.global_storage.borrow_mut();
+        let account = self.account_mut(&mut global_storage, address);
+        let previous = account.code.replace(code);
 
-                    self.execution_machine.stack.push(U256::from(bytes[i]))?;
-                    self.execution_machine.pc.increment_by(1);
-                }
+        self.journal
+            .borrow_mut()
+            .record(JournalEntry::CodeChanged { address, previous });
+    }
 
-                // SHR and SHL are inverted bcs U256 operates in little endian
-                OpCode::SHL => {
-                    let shift = self.execution_machine.stack.pop()?;
-                    let value = self.execution_machine.stack.pop()?;
-                    let result = value >> shift;
+    fn set_storage(&self, address: H160, key: U256, value: U256) {
+        let mut global_storage = self.global_env.global_storage.borrow_mut();
+        let storage = &mut self.account_mut(&mut global_storage, address).storage;
+        let previous = if value.is_zero() {
+            storage.remove(&key)
+        } else {
+            storage.insert(key, value)
+        };
+
+        self.journal
+            .borrow_mut()
+            .record(JournalEntry::StorageChanged {
+                address,
+                key,
+                previous: previous.unwrap_or_default(),
+            });
+    }
 
-                    self.execution_machine.stack.push(result)?;
-                    self.execution_machine.pc.increment_by(1);
-                }
+    fn set_transient_storage(&self, address: H160, key: U256, value: U256) {
+        let mut transient_storage = self.transient_storage.borrow_mut();
+        let previous = if value.is_zero() {
+            transient_storage.remove(&(address, key))
+        } else {
+            transient_storage.insert((address, key), value)
+        };
+
+        self.journal
+            .borrow_mut()
+            .record(JournalEntry::TransientStorageChanged {
+                address,
+                key,
+                previous: previous.unwrap_or_default(),
+            });
+    }
 
-                OpCode::SHR => {
-                    let shift = self.execution_machine.stack.pop()?;
-                    let value = self.execution_machine.stack.pop()?;
-                    let result = value << shift;
+    pub(crate) fn push_log(&self, log: Log) {
+        if let Some(inspector) = &self.inspector {
+            inspector.borrow_mut().log(&log);
+        }
 
-                    self.execution_machine.stack.push(result)?;
-                    self.execution_machine.pc.increment_by(1);
-                }
+        self.logs.borrow_mut().push(log);
+        self.journal.borrow_mut().record(JournalEntry::LogPushed);
+    }
 
-                OpCode::SHA3 => {
-                    let offset = self.execution_machine.stack.pop()?.as_usize();
-                    let size = self.execution_machine.stack.pop()?.as_usize();
-                    let value = self.execution_machine.memory.read_bytes(offset, size);
+    /// Marks `address` as warm, returns true if it was cold before
+    fn access_address(&self, address: H160) -> bool {
+        let is_cold = self.access_set.borrow_mut().access_address(address);
+        if is_cold {
+            self.journal
+                .borrow_mut()
+                .record(JournalEntry::AddressWarmed { address });
+        }
 
-                    let mut sha3 = Sha3_256::new();
-                    sha3.update(&value);
-                    let digest = sha3.finalize();
+        is_cold
+    }
 
-                    self.execution_machine
-                        .stack
-                        .push(U256::from_big_endian(digest.as_slice()))?;
-                    self.execution_machine.pc.increment_by(1);
-                }
+    /// Marks the slot `key` of `address` as warm, returns true if it was cold before
+    fn access_storage_key(&self, address: H160, key: U256) -> bool {
+        let is_cold = self
+            .access_set
+            .borrow_mut()
+            .access_storage_key(address, key);
+        if is_cold {
+            self.journal
+                .borrow_mut()
+                .record(JournalEntry::StorageKeyWarmed { address, key });
+        }
 
-                OpCode::ADDRESS => {
-                    let address = self.execution_env.contract_address;
+        is_cold
+    }
 
-                    self.execution_machine
-                        .stack
-                        .push(U256::from(address.as_bytes()))?;
-                    self.execution_machine.pc.increment_by(1);
-                }
+    /// CREATE and CREATE2, pushes the address of the new contract on the stack or 0 if it failed
+    fn create(&mut self, kind: &OpCode) -> Result<()> {
+        if self.execution_env.is_static {
+            return Err(HaltReason::StaticStateChange(*kind));
+        }
 
-                OpCode::BALANCE => {
-                    let address = convert_u256_to_eth_address(self.execution_machine.stack.pop()?);
+        let value = self.execution_machine.stack.pop()?;
+        let offset = self.execution_machine.stack.pop()?;
+        let size = self.execution_machine.stack.pop()?;
+        let salt = match kind {
+            OpCode::CREATE2 => {
+                let mut salt = H256::zero();
+                self.execution_machine
+                    .stack
+                    .pop()?
+                    .to_big_endian(salt.as_bytes_mut());
+                Some(salt)
+            }
+            _ => None,
+        };
 
-                    if let Some(account) = self.global_env.global_storage.borrow().get(&address) {
-                        self.execution_machine.stack.push(account.balance)?;
-                    } else {
-                        self.execution_machine.stack.push(U256::zero())?;
-                    };
+        let (offset, size) = self.expand_memory(offset, size)?;
+        self.return_data.clear();
 
-                    self.execution_machine.pc.increment_by(1);
-                }
+        let spec = self.global_env.spec_id;
+        let mut cost = 0;
 
-                OpCode::SELFBALANCE => {
-                    let address = self.execution_env.caller;
+        if spec.is_enabled(SpecId::Shanghai) {
+            if size > MAX_INITCODE_SIZE {
+                return Err(HaltReason::InitCodeSizeExceeded);
+            }
 
-                    if let Some(account) = self.global_env.global_storage.borrow().get(&address) {
-                        self.execution_machine.stack.push(account.balance)?;
-                    } else {
-                        self.execution_machine.stack.push(U256::zero())?;
-                    };
+            cost += gas::initcode_cost(size as u64);
+        }
 
-                    self.execution_machine.pc.increment_by(1);
-                }
+        if salt.is_some() {
+            // the init code has to be hashed to get the address
+            cost += gas::sha3_cost(size as u64);
+        }
+        self.gas.record_cost(cost)?;
+
+        let init_code = self.execution_machine.memory.read_bytes(offset, size);
+        let sender = self.execution_env.contract_address;
+        let nonce = self
+            .global_env
+            .global_storage
+            .borrow()
+            .get(&sender)
+            .map(|account| account.nonce)
+            .unwrap_or_default();
+
+        // nothing is executed and no gas is spent
+        if self.depth >= MAX_CALL_DEPTH || self.balance_of(sender) < value || nonce == u64::MAX {
+            self.execution_machine.stack.push(U256::zero())?;
+            return Ok(());
+        }
 
-                OpCode::CALLER => {
-                    let caller = self.execution_env.caller;
+        self.set_nonce(sender, nonce + 1);
+
+        // hashed once, for the address and for the cached analysis
+        let init_code = Bytecode::new_cached(init_code, &self.global_env.jump_tables);
+
+        let address = match salt {
+            Some(salt) => create2_address(sender, salt, init_code.hash()),
+            None => create_address(sender, nonce),
+        };
+
+        self.access_address(address);
+
+        // everything that is left, but one 64th since EIP-150
+        let remaining = self.gas.remaining();
+        let gas_limit = gas::call_gas(spec, remaining, U256::from(remaining));
+        self.gas.record_cost(gas_limit)?;
+
+        // can't deploy where there is already a contract, the gas given is lost, see EIP-684
+        let collision = self
+            .global_env
+            .global_storage
+            .borrow()
+            .get(&address)
+            .is_some_and(|account| {
+                account.nonce != 0
+                    || account.code.as_ref().is_some_and(|code| !code.is_empty())
+                    || !account.storage.is_empty()
+            });
+
+        if collision {
+            self.execution_machine.stack.push(U256::zero())?;
+            return Ok(());
+        }
 
-                    self.execution_machine
-                        .stack
-                        .push(U256::from(caller.as_bytes()))?;
-                    self.execution_machine.pc.increment_by(1);
-                }
+        // the sender's nonce stays incremented even if the creation fails
+        let checkpoint = self.checkpoint();
 
-                OpCode::CODESIZE => {
-                    let code_size = program.len();
-                    self.execution_machine.stack.push(U256::from(code_size))?;
-                    self.execution_machine.pc.increment_by(1);
-                }
+        self.start_contract(address);
 
-                OpCode::CODECOPY => {
-                    let dest_offset = self.execution_machine.stack.pop()?.as_usize();
-                    let offset = self.execution_machine.stack.pop()?.as_usize();
-                    let size = self.execution_machine.stack.pop()?.as_usize();
-                    let code = &program[offset..(offset + size)];
+        self.transfer(sender, address, value);
 
-                    self.execution_machine
-                        .memory
-                        .write_bytes(dest_offset, code.to_vec());
-                    self.execution_machine.pc.increment_by(1);
-                }
+        let execution_env = ExecutionEnvironment {
+            value,
+            caller: sender,
+            calldata: Vec::new(),
+            contract_address: address,
+            gas_limit,
+            is_static: false,
+        };
 
-                OpCode::CALLDATALOAD => {
-                    let len = self.execution_env.calldata.len();
-                    let offset = self.execution_machine.stack.pop()?.as_usize();
+        if let Some(inspector) = &self.inspector {
+            inspector
+                .borrow_mut()
+                .create(*kind, init_code.bytes(), &execution_env);
+        }
 
-                    let data = if offset + 32 > len {
-                        let mut data = self.execution_env.calldata[offset..].to_vec();
-                        data.resize(32, 0);
-                        data
-                    } else {
-                        self.execution_env.calldata[offset..(offset + 32)].to_vec()
-                    };
+        let mut child = self.new_child(execution_env);
+        let result = stacker::maybe_grow(STACK_RED_ZONE, STACK_GROW_SIZE, || {
+            match child.run_frame(&init_code) {
+                Ok(FrameOutcome::Return(code)) => child
+                    .deposit_code(code)
+                    .map(|()| FrameOutcome::Return(Vec::new())),
+                outcome => outcome,
+            }
+        });
 
-                    self.execution_machine
-                        .stack
-                        .push(U256::from_big_endian(&data))?;
-                    self.execution_machine.pc.increment_by(1);
-                }
+        if result.is_err() {
+            child.gas.consume_all();
+        }
 
-                OpCode::CALLDATASIZE => {
-                    let size = self.execution_env.calldata.len();
-                    self.execution_machine.stack.push(U256::from(size))?;
-                    self.execution_machine.pc.increment_by(1);
-                }
+        if let Some(inspector) = &self.inspector {
+            inspector
+                .borrow_mut()
+                .create_end(&frame_end(&result, child.gas.remaining()));
+        }
 
-                OpCode::JUMP => {
-                    let offset = self.execution_machine.stack.pop()?.as_usize();
+        let success = match result {
+            Ok(FrameOutcome::Return(_)) => {
+                self.commit(checkpoint);
+                self.gas.record_refund(child.gas.refunded());
+                true
+            }
 
-                    // check jump destination must be the JUMPDEST opcode
-                    let Some(opcode) =  program.get(offset) else {
-                        bail!("index out of bounds for program offset")
-                    };
+            // unlike an exceptional halt, the gas left is given back
+            Ok(FrameOutcome::Revert(data)) => {
+                self.revert_to(checkpoint);
+                self.return_data = data;
+                false
+            }
 
-                    match OpCode::from(*opcode) {
-                        OpCode::JUMPDEST => self.execution_machine.pc.set_exact(offset),
-                        _ => return Err(eyre::eyre!(OperationError::JumpDestExpected)),
-                    }
-                }
+            Err(_) => {
+                self.revert_to(checkpoint);
+                false
+            }
+        };
 
-                OpCode::JUMPI => {
-                    let counter = self.execution_machine.stack.pop()?.as_usize();
-                    let b = self.execution_machine.stack.pop()?;
+        self.gas.erase_cost(child.gas.remaining());
+        self.execution_machine.stack.push(if success {
+            U256::from(address.as_bytes())
+        } else {
+            U256::zero()
+        })?;
 
-                    if !b.is_zero() {
-                        let Some(opcode) =  program.get(counter) else {
-                            bail!("index out of bounds for program offset")
-                        };
+        Ok(())
+    }
 
-                        // check jump destination must be the JUMPDEST opcode
-                        match OpCode::from(*opcode) {
-                            OpCode::JUMPDEST => self.execution_machine.pc.set_exact(counter),
-                            _ => return Err(eyre::eyre!(OperationError::JumpDestExpected)),
-                        }
-                    } else {
-                        self.execution_machine.pc.increment_by(1)
-                    }
-                }
+    /// Sets up the account of a contract being created, before its init code runs
+    fn start_contract(&self, address: H160) {
+        // contracts start with a nonce of 1 since EIP-161
+        if self.global_env.spec_id.is_enabled(SpecId::SpuriousDragon) {
+            self.set_nonce(address, 1);
+        } else {
+            self.set_nonce(address, 0);
+        }
 
-                OpCode::PC => {
-                    self.execution_machine
-                        .stack
-                        .push(U256::from(self.execution_machine.pc.get()))?;
-                    self.execution_machine.pc.increment_by(1);
-                }
+        self.created_contracts.borrow_mut().insert(address);
+        self.journal
+            .borrow_mut()
+            .record(JournalEntry::ContractCreated { address });
+    }
 
-                OpCode::MSIZE => {
-                    let size = self.execution_machine.memory.used_capacity();
-                    self.execution_machine.stack.push(U256::from(size))?;
-                    self.execution_machine.pc.increment_by(1);
-                }
+    /// SELFDESTRUCT, sends the whole balance to the beneficiary and deletes the account
+    /// at the end of the transaction. Since EIP-6780 only a contract created in the same
+    /// transaction is deleted, any other one just loses its balance.
+    fn selfdestruct(&mut self) -> Result<()> {
+        if self.execution_env.is_static {
+            return Err(HaltReason::StaticStateChange(OpCode::SELFDESTRUCT));
+        }
 
-                OpCode::JUMPDEST => self.execution_machine.pc.increment_by(1),
+        let beneficiary = convert_u256_to_eth_address(self.execution_machine.stack.pop()?);
+        let address = self.execution_env.contract_address;
+        let spec = self.global_env.spec_id;
+        let balance = self.balance_of(address);
 
-                OpCode::PUSH(amount) => construct_push_op!(amount, self, program),
+        let creates_account = {
+            let global_storage = self.global_env.global_storage.borrow();
+            let account = global_storage.get(&beneficiary);
 
-                OpCode::POP => {
-                    self.execution_machine.stack.pop()?;
-                    self.execution_machine.pc.increment_by(1);
-                }
+            if spec.is_enabled(SpecId::SpuriousDragon) {
+                !balance.is_zero() && account.is_none_or(|account| account.is_empty())
+            } else {
+                account.is_none()
+            }
+        };
 
-                OpCode::DUP(amount) => construct_dup_op!(amount, self),
+        let is_cold = self.access_address(beneficiary);
+        self.gas
+            .record_cost(gas::selfdestruct_cost(spec, is_cold, creates_account))?;
 
-                OpCode::SWAP(amount) => construct_swap_op!(amount, self),
+        if let Some(inspector) = &self.inspector {
+            inspector
+                .borrow_mut()
+                .selfdestruct(address, beneficiary, balance);
+        }
 
-                OpCode::MLOAD => {
-                    let offset = self.execution_machine.stack.pop()?.as_usize();
-                    let word = self.execution_machine.memory.read_bytes(offset, 32);
-                    self.execution_machine
-                        .stack
-                        .push(U256::from_big_endian(&word))?;
-                    self.execution_machine.pc.increment_by(1);
-                }
+        let deleted =
+            !spec.is_enabled(SpecId::Cancun) || self.created_contracts.borrow().contains(&address);
 
-                OpCode::MSTORE => {
-                    let offset = self.execution_machine.stack.pop()?;
-                    let value = self.execution_machine.stack.pop()?;
+        // the refund is only given once per account
+        if deleted && self.selfdestructs.borrow_mut().insert(address) {
+            self.gas.record_refund(gas::selfdestruct_refund(spec));
+            self.journal
+                .borrow_mut()
+                .record(JournalEntry::SelfDestructed { address });
+        }
 
-                    let mut value_be = [0u8; 32];
-                    value.to_big_endian(&mut value_be);
+        if !balance.is_zero() {
+            if beneficiary != address {
+                self.transfer(address, beneficiary, balance);
+            } else if deleted {
+                // sending the balance to itself burns it
+                self.set_balance(address, U256::zero());
+            }
+        }
 
-                    self.execution_machine
-                        .memory
-                        .write_bytes(offset.as_usize(), value_be.to_vec());
-                    self.execution_machine.pc.increment_by(1);
-                }
+        Ok(())
+    }
 
-                OpCode::MSTORE8 => {
-                    let offset = self.execution_machine.stack.pop()?.as_usize();
-                    let value = self.execution_machine.stack.pop()?;
-                    let byte = value.byte(31);
-                    self.execution_machine
-                        .memory
-                        .write_bytes(offset, vec![byte]);
-                    self.execution_machine.pc.increment_by(1);
-                }
+    /// Stores the code returned by the init code as the code of the contract being created
+    fn deposit_code(&mut self, code: Vec<u8>) -> Result<()> {
+        let spec = self.global_env.spec_id;
 
-                OpCode::LOG(amount) => {
-                    // let _ = self.execution_machine.stack.pop()?.as_usize();
-                    // let _ = self.execution_machine.stack.pop()?;
-                    // let topic1 = self.execution_machine.stack.pop()?;
+        if spec.is_enabled(SpecId::SpuriousDragon) && code.len() > MAX_CODE_SIZE {
+            return Err(HaltReason::CodeSizeExceeded);
+        }
 
-                    // let log = vec![topic1];
+        if spec.is_enabled(SpecId::London) && code.first() == Some(&0xEF) {
+            return Err(HaltReason::InvalidCodePrefix);
+        }
 
-                    // self.logs.borrow_mut().push(log);
-                    // self.execution_machine.pc.increment_by(1);
-                    construct_log_op!(amount, self)
-                }
+        if self
+            .gas
+            .record_cost(gas::CODE_DEPOSIT * code.len() as u64)
+            .is_err()
+        {
+            // the contract was left without code until EIP-2 made it fail
+            if spec.is_enabled(SpecId::Homestead) {
+                return Err(HaltReason::OutOfGas);
+            }
 
-                OpCode::CALLVALUE => {
-                    let callvalue = self.execution_env.value;
-                    self.execution_machine.stack.push(callvalue)?;
-                    self.execution_machine.pc.increment_by(1);
+            return Ok(());
+        }
+
+        let code = Bytecode::new_cached(code, &self.global_env.jump_tables);
+        self.set_code(self.execution_env.contract_address, code);
+
+        Ok(())
+    }
+
+    /// CALL, CALLCODE, DELEGATECALL and STATICCALL, pushes 1 on the stack if the call succeeded
+    fn call(&mut self, kind: &OpCode) -> Result<()> {
+        let requested_gas = self.execution_machine.stack.pop()?;
+        let address = convert_u256_to_eth_address(self.execution_machine.stack.pop()?);
+        let value = match kind {
+            OpCode::CALL | OpCode::CALLCODE => self.execution_machine.stack.pop()?,
+            _ => U256::zero(),
+        };
+        let args_offset = self.execution_machine.stack.pop()?;
+        let args_size = self.execution_machine.stack.pop()?;
+        let ret_offset = self.execution_machine.stack.pop()?;
+        let ret_size = self.execution_machine.stack.pop()?;
+        self.return_data.clear();
+
+        if self.execution_env.is_static && matches!(kind, OpCode::CALL) && !value.is_zero() {
+            return Err(HaltReason::StaticStateChange(OpCode::CALL));
+        }
+
+        let (args_offset, args_size) = self.expand_memory(args_offset, args_size)?;
+        let (ret_offset, ret_size) = self.expand_memory(ret_offset, ret_size)?;
+
+        let spec = self.global_env.spec_id;
+        let is_cold = self.access_address(address);
+        let mut cost = gas::account_access_cost(spec, kind, is_cold);
+
+        if !value.is_zero() {
+            cost += gas::CALL_VALUE;
+        }
+
+        // since EIP-161 only sending value to an empty account creates it
+        let creates_account = {
+            let global_storage = self.global_env.global_storage.borrow();
+            let account = global_storage.get(&address);
+
+            if spec.is_enabled(SpecId::SpuriousDragon) {
+                !value.is_zero() && account.is_none_or(|account| account.is_empty())
+            } else {
+                account.is_none()
+            }
+        };
+
+        if matches!(kind, OpCode::CALL) && creates_account {
+            cost += gas::NEW_ACCOUNT;
+        }
+
+        self.gas.record_cost(cost)?;
+
+        let gas_limit = gas::call_gas(spec, self.gas.remaining(), requested_gas);
+        self.gas.record_cost(gas_limit)?;
+
+        let stipend = if value.is_zero() {
+            0
+        } else {
+            gas::CALL_STIPEND
+        };
+
+        // the call fails without being executed, so the gas given to it is returned,
+        // stipend included like the other clients do
+        if self.depth >= MAX_CALL_DEPTH
+            || self.balance_of(self.execution_env.contract_address) < value
+        {
+            self.gas.erase_cost(gas_limit + stipend);
+            self.execution_machine.stack.push(U256::zero())?;
+            return Ok(());
+        }
+
+        let calldata = self
+            .execution_machine
+            .memory
+            .read_bytes(args_offset, args_size);
+
+        let current = &self.execution_env;
+        let execution_env = match kind {
+            OpCode::CALL => ExecutionEnvironment {
+                value,
+                caller: current.contract_address,
+                calldata,
+                contract_address: address,
+                gas_limit: gas_limit + stipend,
+                is_static: current.is_static,
+            },
+
+            // runs the code of `address` as if it was our own
+            OpCode::CALLCODE => ExecutionEnvironment {
+                value,
+                caller: current.contract_address,
+                calldata,
+                contract_address: current.contract_address,
+                gas_limit: gas_limit + stipend,
+                is_static: current.is_static,
+            },
+
+            // same as CALLCODE but the caller and value are kept as well
+            OpCode::DELEGATECALL => ExecutionEnvironment {
+                value: current.value,
+                caller: current.caller,
+                calldata,
+                contract_address: current.contract_address,
+                gas_limit,
+                is_static: current.is_static,
+            },
+
+            _ => ExecutionEnvironment {
+                value,
+                caller: current.contract_address,
+                calldata,
+                contract_address: address,
+                gas_limit,
+                is_static: true,
+            },
+        };
+
+        let checkpoint = self.checkpoint();
+
+        // a call without value leaves the callee untouched unless it creates it
+        if matches!(kind, OpCode::CALL) && (creates_account || !value.is_zero()) {
+            self.transfer(self.execution_env.contract_address, address, value);
+        }
+
+        if let Some(inspector) = &self.inspector {
+            inspector.borrow_mut().call(*kind, address, &execution_env);
+        }
+
+        let (result, remaining, refunded) = match self.precompiles.get(&address) {
+            // any error of a precompile consumes all the gas given to it
+            Some(precompile) => {
+                match precompile(
+                    &execution_env.calldata,
+                    execution_env.gas_limit,
+                    self.global_env.spec_id,
+                ) {
+                    Ok(output) => (
+                        Ok(FrameOutcome::Return(output.data)),
+                        execution_env.gas_limit - output.gas_used,
+                        0,
+                    ),
+                    Err(error) => (Err(error.into()), 0, 0),
                 }
+            }
 
-                OpCode::RETURN => {
-                    let offset = self.execution_machine.stack.pop()?.as_usize();
-                    let size = self.execution_machine.stack.pop()?.as_usize();
-                    let value = self.execution_machine.memory.read_bytes(offset, size);
+            None => {
+                let code = self.code_of(address);
+                let mut child = self.new_child(execution_env);
+                let result =
+                    stacker::maybe_grow(STACK_RED_ZONE, STACK_GROW_SIZE, || child.run_frame(&code));
 
-                    println!("{value:x?} {}", value.len());
+                (result, child.gas.remaining(), child.gas.refunded())
+            }
+        };
 
-                    for i in value.into_iter() {
-                        result.push(i);
-                    }
+        if let Some(inspector) = &self.inspector {
+            inspector
+                .borrow_mut()
+                .call_end(&frame_end(&result, remaining));
+        }
+
+        let (success, data) = match result {
+            Ok(FrameOutcome::Return(data)) => {
+                self.commit(checkpoint);
+                self.gas.record_refund(refunded);
+                (true, data)
+            }
+
+            Ok(FrameOutcome::Revert(data)) => {
+                self.revert_to(checkpoint);
+                (false, data)
+            }
+
+            Err(_) => {
+                self.revert_to(checkpoint);
+                (false, Vec::new())
+            }
+        };
+
+        let len = ret_size.min(data.len());
+        self.execution_machine
+            .memory
+            .write_bytes(ret_offset, data[..len].to_vec());
+        self.return_data = data;
+
+        self.gas.erase_cost(remaining);
+        self.execution_machine
+            .stack
+            .push(if success { U256::one() } else { U256::zero() })?;
+
+        Ok(())
+    }
+
+    /// Charges for expanding memory to fit `size` bytes starting from `offset`,
+    /// and returns both as `usize`. A zero `size` never touches memory.
+    fn expand_memory(
+        &mut self,
+        offset: U256,
+        size: U256,
+    ) -> std::result::Result<(usize, usize), GasError> {
+        if size.is_zero() {
+            return Ok((0, 0));
+        }
 
-                    return Ok(ExecutionResult { data: result });
+        // no gas limit can pay for this much memory anyway
+        let limit = U256::from(u32::MAX);
+        if offset > limit || size > limit {
+            return Err(GasError::OutOfGas);
+        }
+
+        let (offset, size) = (offset.as_u64(), size.as_u64());
+        let current_words = self.execution_machine.memory.size_in_words() as u64;
+        let new_words = gas::num_words(offset + size);
+
+        // paid for before anything is allocated
+        self.gas
+            .record_cost(gas::memory_expansion_cost(current_words, new_words))?;
+
+        if offset + size > MAX_MEMORY_SIZE {
+            return Err(GasError::OutOfGas);
+        }
+
+        Ok((offset as usize, size as usize))
+    }
+
+    fn execute(&mut self, code: &Bytecode) -> Result<FrameOutcome> {
+        let outcome = match self.interpret(code) {
+            Status::Continue | Status::Stop => FrameOutcome::Return(Vec::new()),
+            Status::Return => FrameOutcome::Return(std::mem::take(&mut self.output)),
+            Status::Revert => FrameOutcome::Revert(std::mem::take(&mut self.output)),
+            Status::Halt(reason) => return Err(reason),
+        };
+
+        Ok(outcome)
+    }
+
+    /// Runs `code` until the frame ends, dispatching every operation through the instruction table
+    fn interpret(&mut self, code: &Bytecode) -> Status {
+        let spec = self.global_env.spec_id;
+        let padded = code.padded();
+        let inspector = self.inspector.clone();
+
+        loop {
+            // the padding ends with a STOP, so this only fails after a jump past the end
+            let Some(&opcode) = padded.get(self.execution_machine.pc.get()) else {
+                return Status::Stop;
+            };
+
+            let status = match &inspector {
+                None => self.step(code, spec, opcode),
+                Some(inspector) => {
+                    inspector.borrow_mut().step(self, OpCode::from(opcode));
+                    let status = self.step(code, spec, opcode);
+
+                    let halt = match status {
+                        Status::Halt(reason) => Some(reason),
+                        _ => None,
+                    };
+                    inspector
+                        .borrow_mut()
+                        .step_end(self, OpCode::from(opcode), halt);
+
+                    status
                 }
+            };
 
-                OpCode::INVALID => bail!(OperationError::InvalidOperation(*opcode)),
+            if status != Status::Continue {
+                return status;
             }
         }
+    }
+
+    /// Checks that `opcode` can run and runs it
+    #[inline(always)]
+    fn step(&mut self, code: &Bytecode, spec: SpecId, opcode: u8) -> Status {
+        let Some(info) = &OPCODES[opcode as usize] else {
+            return Status::Halt(HaltReason::UndefinedOpcode(opcode));
+        };
+
+        if !spec.is_enabled(info.introduced_in) {
+            return Status::Halt(HaltReason::NotActivated(OpCode::from(opcode)));
+        }
+
+        if let Err(error) = self
+            .execution_machine
+            .stack
+            .require(info.inputs as usize, info.outputs as usize)
+        {
+            return Status::Halt(error.into());
+        }
+
+        if self.gas.record_cost(info.gas).is_err() {
+            return Status::Halt(HaltReason::OutOfGas);
+        }
+
+        self.execution_machine.pc.increment_by(1);
+
+        INSTRUCTIONS[opcode as usize](self, code)
+    }
+}
 
-        Ok(ExecutionResult { data: result })
+/// What an inspector is told about a nested frame that ended
+fn frame_end(result: &Result<FrameOutcome>, gas_remaining: u64) -> FrameEnd<'_> {
+    let (status, output) = match result {
+        Ok(FrameOutcome::Return(data)) => (ExecutionStatus::Success, data.as_slice()),
+        Ok(FrameOutcome::Revert(data)) => (ExecutionStatus::Revert, data.as_slice()),
+        Err(reason) => (ExecutionStatus::Halt(*reason), &[][..]),
+    };
+
+    FrameEnd {
+        status,
+        output,
+        gas_remaining,
     }
 }
@@ -0,0 +1,237 @@
+//! The instruction set of the interpreter, one handler per opcode dispatched through a table
+
+use super::ExecutionContext;
+use crate::bytecode::Bytecode;
+use crate::halt::HaltReason;
+
+/// How an instruction ended, anything but `Continue` ends the frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Status {
+    Continue,
+    Stop,
+    Return,
+    Revert,
+    Halt(HaltReason),
+}
+
+pub(crate) type Instruction = fn(&mut ExecutionContext, &Bytecode) -> Status;
+
+macro_rules! pop {
+    ($ctx:ident) => {
+        match $ctx.execution_machine.stack.pop() {
+            Ok(value) => value,
+            Err(_) => return Status::Halt($crate::halt::HaltReason::StackUnderflow),
+        }
+    };
+}
+
+macro_rules! push {
+    ($ctx:ident, $value:expr) => {
+        if $ctx.execution_machine.stack.push($value).is_err() {
+            return Status::Halt($crate::halt::HaltReason::StackOverflow);
+        }
+    };
+}
+
+macro_rules! gas {
+    ($ctx:ident, $cost:expr) => {
+        if $ctx.gas.record_cost($cost).is_err() {
+            return Status::Halt($crate::halt::HaltReason::OutOfGas);
+        }
+    };
+}
+
+/// Charges for the memory expansion and evaluates to `(offset, size)` as `usize`
+macro_rules! memory {
+    ($ctx:ident, $offset:expr, $size:expr) => {
+        match $ctx.expand_memory($offset, $size) {
+            Ok(range) => range,
+            Err(_) => return Status::Halt($crate::halt::HaltReason::OutOfGas),
+        }
+    };
+}
+
+/// Ends the frame exceptionally
+macro_rules! halt {
+    ($reason:expr) => {
+        return Status::Halt($reason)
+    };
+}
+
+/// Halts the frame if `$result`, from one of the context's own operations, failed
+macro_rules! attempt {
+    ($result:expr) => {
+        if let Err(reason) = $result {
+            return Status::Halt(reason);
+        }
+    };
+}
+
+mod arithmetic;
+mod bitwise;
+mod control;
+mod host;
+mod memory;
+mod stack;
+mod system;
+
+/// The handler of every opcode, the undefined ones are caught before dispatch
+pub(crate) static INSTRUCTIONS: [Instruction; 256] = {
+    let mut table: [Instruction; 256] = [control::unsupported; 256];
+
+    table[0x00] = control::stop;
+    table[0x01] = arithmetic::add;
+    table[0x02] = arithmetic::mul;
+    table[0x03] = arithmetic::sub;
+    table[0x04] = arithmetic::div;
+    table[0x05] = arithmetic::sdiv;
+    table[0x06] = arithmetic::rem;
+    table[0x07] = arithmetic::smod;
+    table[0x08] = arithmetic::addmod;
+    table[0x09] = arithmetic::mulmod;
+    table[0x0A] = arithmetic::exp;
+    table[0x0B] = arithmetic::signextend;
+
+    table[0x10] = bitwise::lt;
+    table[0x11] = bitwise::gt;
+    table[0x12] = bitwise::slt;
+    table[0x13] = bitwise::sgt;
+    table[0x14] = bitwise::eq;
+    table[0x15] = bitwise::iszero;
+    table[0x16] = bitwise::and;
+    table[0x17] = bitwise::or;
+    table[0x18] = bitwise::xor;
+    table[0x19] = bitwise::not;
+    table[0x1A] = bitwise::byte;
+    table[0x1B] = bitwise::shl;
+    table[0x1C] = bitwise::shr;
+    table[0x1D] = bitwise::sar;
+
+    table[0x20] = system::sha3;
+
+    table[0x30] = system::address;
+    table[0x31] = host::balance;
+    table[0x33] = system::caller;
+    table[0x34] = system::callvalue;
+    table[0x35] = system::calldataload;
+    table[0x36] = system::calldatasize;
+    table[0x38] = system::codesize;
+    table[0x39] = system::codecopy;
+    table[0x3B] = host::extcodesize;
+    table[0x3C] = host::extcodecopy;
+    table[0x3D] = system::returndatasize;
+    table[0x3E] = system::returndatacopy;
+    table[0x3F] = host::extcodehash;
+
+    table[0x40] = host::blockhash;
+    table[0x41] = host::coinbase;
+    table[0x42] = host::timestamp;
+    table[0x43] = host::number;
+    table[0x44] = host::prevrandao;
+    table[0x45] = host::gaslimit;
+    table[0x46] = host::chainid;
+    table[0x47] = host::selfbalance;
+    table[0x48] = host::basefee;
+
+    table[0x50] = stack::pop;
+    table[0x51] = memory::mload;
+    table[0x52] = memory::mstore;
+    table[0x53] = memory::mstore8;
+    table[0x54] = host::sload;
+    table[0x55] = host::sstore;
+    table[0x56] = control::jump;
+    table[0x57] = control::jumpi;
+    table[0x58] = control::pc;
+    table[0x59] = memory::msize;
+    table[0x5A] = system::gas;
+    table[0x5B] = control::jumpdest;
+    table[0x5C] = host::tload;
+    table[0x5D] = host::tstore;
+
+    table[0x5F] = stack::push::<0>;
+    table[0x60] = stack::push::<1>;
+    table[0x61] = stack::push::<2>;
+    table[0x62] = stack::push::<3>;
+    table[0x63] = stack::push::<4>;
+    table[0x64] = stack::push::<5>;
+    table[0x65] = stack::push::<6>;
+    table[0x66] = stack::push::<7>;
+    table[0x67] = stack::push::<8>;
+    table[0x68] = stack::push::<9>;
+    table[0x69] = stack::push::<10>;
+    table[0x6A] = stack::push::<11>;
+    table[0x6B] = stack::push::<12>;
+    table[0x6C] = stack::push::<13>;
+    table[0x6D] = stack::push::<14>;
+    table[0x6E] = stack::push::<15>;
+    table[0x6F] = stack::push::<16>;
+    table[0x70] = stack::push::<17>;
+    table[0x71] = stack::push::<18>;
+    table[0x72] = stack::push::<19>;
+    table[0x73] = stack::push::<20>;
+    table[0x74] = stack::push::<21>;
+    table[0x75] = stack::push::<22>;
+    table[0x76] = stack::push::<23>;
+    table[0x77] = stack::push::<24>;
+    table[0x78] = stack::push::<25>;
+    table[0x79] = stack::push::<26>;
+    table[0x7A] = stack::push::<27>;
+    table[0x7B] = stack::push::<28>;
+    table[0x7C] = stack::push::<29>;
+    table[0x7D] = stack::push::<30>;
+    table[0x7E] = stack::push::<31>;
+    table[0x7F] = stack::push::<32>;
+
+    table[0x80] = stack::dup::<1>;
+    table[0x81] = stack::dup::<2>;
+    table[0x82] = stack::dup::<3>;
+    table[0x83] = stack::dup::<4>;
+    table[0x84] = stack::dup::<5>;
+    table[0x85] = stack::dup::<6>;
+    table[0x86] = stack::dup::<7>;
+    table[0x87] = stack::dup::<8>;
+    table[0x88] = stack::dup::<9>;
+    table[0x89] = stack::dup::<10>;
+    table[0x8A] = stack::dup::<11>;
+    table[0x8B] = stack::dup::<12>;
+    table[0x8C] = stack::dup::<13>;
+    table[0x8D] = stack::dup::<14>;
+    table[0x8E] = stack::dup::<15>;
+    table[0x8F] = stack::dup::<16>;
+
+    table[0x90] = stack::swap::<1>;
+    table[0x91] = stack::swap::<2>;
+    table[0x92] = stack::swap::<3>;
+    table[0x93] = stack::swap::<4>;
+    table[0x94] = stack::swap::<5>;
+    table[0x95] = stack::swap::<6>;
+    table[0x96] = stack::swap::<7>;
+    table[0x97] = stack::swap::<8>;
+    table[0x98] = stack::swap::<9>;
+    table[0x99] = stack::swap::<10>;
+    table[0x9A] = stack::swap::<11>;
+    table[0x9B] = stack::swap::<12>;
+    table[0x9C] = stack::swap::<13>;
+    table[0x9D] = stack::swap::<14>;
+    table[0x9E] = stack::swap::<15>;
+    table[0x9F] = stack::swap::<16>;
+
+    table[0xA0] = host::log::<0>;
+    table[0xA1] = host::log::<1>;
+    table[0xA2] = host::log::<2>;
+    table[0xA3] = host::log::<3>;
+    table[0xA4] = host::log::<4>;
+
+    table[0xF0] = host::create;
+    table[0xF1] = host::call;
+    table[0xF2] = host::callcode;
+    table[0xF3] = control::ret;
+    table[0xF4] = host::delegatecall;
+    table[0xF5] = host::create2;
+    table[0xFA] = host::staticcall;
+    table[0xFD] = control::revert;
+    table[0xFE] = control::invalid;
+    table[0xFF] = host::selfdestruct;
+
+    table
+};
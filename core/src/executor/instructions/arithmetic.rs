@@ -0,0 +1,103 @@
+use ethereum_types::{U256, U512};
+
+use super::Status;
+use crate::bytecode::Bytecode;
+use crate::executor::ExecutionContext;
+use crate::{gas, i256};
+
+pub(super) fn add(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let a = pop!(ctx);
+    let b = pop!(ctx);
+    push!(ctx, a.overflowing_add(b).0);
+    Status::Continue
+}
+
+pub(super) fn sub(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let a = pop!(ctx);
+    let b = pop!(ctx);
+    push!(ctx, a.overflowing_sub(b).0);
+    Status::Continue
+}
+
+pub(super) fn mul(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let a = pop!(ctx);
+    let b = pop!(ctx);
+    push!(ctx, a.overflowing_mul(b).0);
+    Status::Continue
+}
+
+pub(super) fn div(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let a = pop!(ctx);
+    let b = pop!(ctx);
+    push!(ctx, a.checked_div(b).unwrap_or(U256::zero()));
+    Status::Continue
+}
+
+pub(super) fn sdiv(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let a = pop!(ctx);
+    let b = pop!(ctx);
+    push!(ctx, i256::div(a, b));
+    Status::Continue
+}
+
+/// MOD
+pub(super) fn rem(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let a = pop!(ctx);
+    let b = pop!(ctx);
+    push!(ctx, a.checked_rem(b).unwrap_or(U256::zero()));
+    Status::Continue
+}
+
+pub(super) fn smod(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let a = pop!(ctx);
+    let b = pop!(ctx);
+    push!(ctx, i256::rem(a, b));
+    Status::Continue
+}
+
+pub(super) fn addmod(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let a = pop!(ctx);
+    let b = pop!(ctx);
+    let n = pop!(ctx);
+    push!(ctx, reduce(U512::from(a) + U512::from(b), n));
+    Status::Continue
+}
+
+pub(super) fn mulmod(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let a = pop!(ctx);
+    let b = pop!(ctx);
+    let n = pop!(ctx);
+    push!(ctx, reduce(U512::from(a) * U512::from(b), n));
+    Status::Continue
+}
+
+/// `value % n` for the intermediate results of ADDMOD and MULMOD, zero if `n` is zero
+fn reduce(value: U512, n: U256) -> U256 {
+    if n.is_zero() {
+        return U256::zero();
+    }
+
+    // the remainder is smaller than `n`, so it fits in the lower half
+    let remainder = value % U512::from(n);
+    U256([
+        remainder.0[0],
+        remainder.0[1],
+        remainder.0[2],
+        remainder.0[3],
+    ])
+}
+
+pub(super) fn exp(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let a = pop!(ctx);
+    let exponent = pop!(ctx);
+    gas!(ctx, gas::exp_cost(ctx.global_env.spec_id, exponent));
+    push!(ctx, a.overflowing_pow(exponent).0);
+    Status::Continue
+}
+
+pub(super) fn signextend(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let byte_index = pop!(ctx);
+    let value = pop!(ctx);
+    push!(ctx, i256::sign_extend(byte_index, value));
+    Status::Continue
+}
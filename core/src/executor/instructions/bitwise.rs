@@ -0,0 +1,127 @@
+use ethereum_types::U256;
+
+use super::Status;
+use crate::bytecode::Bytecode;
+use crate::executor::ExecutionContext;
+use crate::i256;
+
+fn bool_to_u256(value: bool) -> U256 {
+    if value {
+        U256::one()
+    } else {
+        U256::zero()
+    }
+}
+
+/// Applies a logical shift, everything is shifted out from 256 bits on
+fn shifted(value: U256, shift: U256, op: fn(U256, usize) -> U256) -> U256 {
+    if shift < U256::from(256) {
+        op(value, shift.as_usize())
+    } else {
+        U256::zero()
+    }
+}
+
+pub(super) fn lt(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let a = pop!(ctx);
+    let b = pop!(ctx);
+    push!(ctx, bool_to_u256(a < b));
+    Status::Continue
+}
+
+pub(super) fn gt(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let a = pop!(ctx);
+    let b = pop!(ctx);
+    push!(ctx, bool_to_u256(a > b));
+    Status::Continue
+}
+
+pub(super) fn slt(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let a = pop!(ctx);
+    let b = pop!(ctx);
+    push!(ctx, bool_to_u256(i256::cmp(a, b).is_lt()));
+    Status::Continue
+}
+
+pub(super) fn sgt(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let a = pop!(ctx);
+    let b = pop!(ctx);
+    push!(ctx, bool_to_u256(i256::cmp(a, b).is_gt()));
+    Status::Continue
+}
+
+pub(super) fn eq(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let a = pop!(ctx);
+    let b = pop!(ctx);
+    push!(ctx, bool_to_u256(a == b));
+    Status::Continue
+}
+
+pub(super) fn iszero(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let a = pop!(ctx);
+    push!(ctx, bool_to_u256(a.is_zero()));
+    Status::Continue
+}
+
+pub(super) fn and(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let a = pop!(ctx);
+    let b = pop!(ctx);
+    push!(ctx, a & b);
+    Status::Continue
+}
+
+pub(super) fn or(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let a = pop!(ctx);
+    let b = pop!(ctx);
+    push!(ctx, a | b);
+    Status::Continue
+}
+
+pub(super) fn xor(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let a = pop!(ctx);
+    let b = pop!(ctx);
+    push!(ctx, a ^ b);
+    Status::Continue
+}
+
+pub(super) fn not(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let a = pop!(ctx);
+    push!(ctx, !a);
+    Status::Continue
+}
+
+pub(super) fn byte(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let i = pop!(ctx);
+    let x = pop!(ctx);
+
+    // `i` counts from the most significant byte, `U256::byte` from the least
+    let byte = if i < U256::from(32) {
+        x.byte(31 - i.as_usize())
+    } else {
+        0
+    };
+
+    push!(ctx, U256::from(byte));
+    Status::Continue
+}
+
+pub(super) fn shl(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let shift = pop!(ctx);
+    let value = pop!(ctx);
+    push!(ctx, shifted(value, shift, |value, shift| value << shift));
+    Status::Continue
+}
+
+pub(super) fn shr(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let shift = pop!(ctx);
+    let value = pop!(ctx);
+    push!(ctx, shifted(value, shift, |value, shift| value >> shift));
+    Status::Continue
+}
+
+pub(super) fn sar(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let shift = pop!(ctx);
+    let value = pop!(ctx);
+    push!(ctx, i256::sar(value, shift));
+    Status::Continue
+}
@@ -0,0 +1,89 @@
+use ethereum_types::U256;
+
+use super::Status;
+use crate::analysis::JumpTable;
+use crate::bytecode::Bytecode;
+use crate::executor::ExecutionContext;
+use crate::halt::HaltReason;
+use crate::operation::OpCode;
+
+pub(super) fn stop(_ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    Status::Stop
+}
+
+pub(super) fn jump(ctx: &mut ExecutionContext, code: &Bytecode) -> Status {
+    let destination = pop!(ctx);
+
+    // check jump destination must be a JUMPDEST outside of PUSH data
+    match jump_destination(code.jump_table(), destination) {
+        Some(offset) => ctx.execution_machine.pc.set_exact(offset),
+        None => halt!(HaltReason::InvalidJump),
+    }
+
+    Status::Continue
+}
+
+pub(super) fn jumpi(ctx: &mut ExecutionContext, code: &Bytecode) -> Status {
+    let destination = pop!(ctx);
+    let condition = pop!(ctx);
+
+    if !condition.is_zero() {
+        match jump_destination(code.jump_table(), destination) {
+            Some(offset) => ctx.execution_machine.pc.set_exact(offset),
+            None => halt!(HaltReason::InvalidJump),
+        }
+    }
+
+    Status::Continue
+}
+
+pub(super) fn jumpdest(_ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    Status::Continue
+}
+
+/// PC, the counter has already moved past the operation
+pub(super) fn pc(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let pc = ctx.execution_machine.pc.get() - 1;
+    push!(ctx, U256::from(pc));
+    Status::Continue
+}
+
+/// RETURN
+pub(super) fn ret(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let offset = pop!(ctx);
+    let size = pop!(ctx);
+    let (offset, size) = memory!(ctx, offset, size);
+    ctx.output = ctx.execution_machine.memory.read_bytes(offset, size);
+
+    Status::Return
+}
+
+/// like RETURN, but the changes made by the frame are discarded
+pub(super) fn revert(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let offset = pop!(ctx);
+    let size = pop!(ctx);
+    let (offset, size) = memory!(ctx, offset, size);
+    ctx.output = ctx.execution_machine.memory.read_bytes(offset, size);
+
+    Status::Revert
+}
+
+pub(super) fn invalid(_ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    Status::Halt(HaltReason::InvalidOpcode)
+}
+
+/// Operations that are defined but not supported yet
+pub(super) fn unsupported(ctx: &mut ExecutionContext, code: &Bytecode) -> Status {
+    let opcode = code.padded()[ctx.execution_machine.pc.get() - 1];
+    Status::Halt(HaltReason::NotSupported(OpCode::from(opcode)))
+}
+
+/// The offset to jump to if `destination` is a valid JUMPDEST
+fn jump_destination(jump_table: &JumpTable, destination: U256) -> Option<usize> {
+    if destination > U256::from(usize::MAX) {
+        return None;
+    }
+
+    let offset = destination.as_usize();
+    jump_table.is_valid(offset).then_some(offset)
+}
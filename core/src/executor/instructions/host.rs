@@ -0,0 +1,267 @@
+use ethereum_types::{H256, U256};
+
+use super::Status;
+use crate::bytecode::Bytecode;
+use crate::executor::ExecutionContext;
+use crate::gas;
+use crate::halt::HaltReason;
+use crate::log::Log;
+use crate::operation::OpCode;
+use crate::spec::SpecId;
+use crate::utils::{convert_u256_to_eth_address, crypto::KECCAK_EMPTY};
+
+pub(super) fn balance(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let address = convert_u256_to_eth_address(pop!(ctx));
+    let is_cold = ctx.access_address(address);
+    gas!(
+        ctx,
+        gas::account_access_cost(ctx.global_env.spec_id, &OpCode::BALANCE, is_cold)
+    );
+
+    push!(ctx, ctx.balance_of(address));
+    Status::Continue
+}
+
+pub(super) fn extcodesize(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let address = convert_u256_to_eth_address(pop!(ctx));
+    let is_cold = ctx.access_address(address);
+    gas!(
+        ctx,
+        gas::account_access_cost(ctx.global_env.spec_id, &OpCode::EXTCODESIZE, is_cold)
+    );
+
+    push!(ctx, U256::from(ctx.code_of(address).len()));
+    Status::Continue
+}
+
+pub(super) fn extcodecopy(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let address = convert_u256_to_eth_address(pop!(ctx));
+    let dest_offset = pop!(ctx);
+    let offset = pop!(ctx);
+    let size = pop!(ctx);
+
+    let (dest_offset, size) = memory!(ctx, dest_offset, size);
+    let is_cold = ctx.access_address(address);
+    gas!(
+        ctx,
+        gas::account_access_cost(ctx.global_env.spec_id, &OpCode::EXTCODECOPY, is_cold)
+            + gas::copy_cost(size as u64)
+    );
+
+    let data = ctx.code_of(address).copy(offset, size);
+    ctx.execution_machine.memory.write_bytes(dest_offset, data);
+    Status::Continue
+}
+
+/// 0 for accounts that don't exist or are empty, see EIP-1052 and EIP-161
+pub(super) fn extcodehash(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let address = convert_u256_to_eth_address(pop!(ctx));
+    let is_cold = ctx.access_address(address);
+    gas!(
+        ctx,
+        gas::account_access_cost(ctx.global_env.spec_id, &OpCode::EXTCODEHASH, is_cold)
+    );
+
+    let hash = match ctx.global_env.global_storage.borrow().get(&address) {
+        Some(account) if !account.is_empty() => {
+            account.code.as_ref().map_or(KECCAK_EMPTY, Bytecode::hash)
+        }
+        _ => H256::zero(),
+    };
+
+    push!(ctx, U256::from_big_endian(hash.as_bytes()));
+    Status::Continue
+}
+
+pub(super) fn blockhash(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let number = pop!(ctx);
+    let hash = ctx
+        .global_env
+        .current_block
+        .block_hash(number)
+        .unwrap_or_default();
+
+    push!(ctx, U256::from_big_endian(hash.as_bytes()));
+    Status::Continue
+}
+
+pub(super) fn coinbase(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let coinbase = ctx.global_env.current_block.coinbase;
+    push!(ctx, U256::from(coinbase.as_bytes()));
+    Status::Continue
+}
+
+pub(super) fn timestamp(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    push!(ctx, U256::from(ctx.global_env.current_block.timestamp));
+    Status::Continue
+}
+
+pub(super) fn number(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    push!(ctx, U256::from(ctx.global_env.current_block.block_number));
+    Status::Continue
+}
+
+/// DIFFICULTY before the merge, see EIP-4399
+pub(super) fn prevrandao(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let block = &ctx.global_env.current_block;
+    let value = if ctx.global_env.spec_id.is_enabled(SpecId::Merge) {
+        U256::from_big_endian(block.prevrandao.as_bytes())
+    } else {
+        block.difficulty
+    };
+
+    push!(ctx, value);
+    Status::Continue
+}
+
+pub(super) fn gaslimit(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    push!(ctx, U256::from(ctx.global_env.current_block.gas_limit));
+    Status::Continue
+}
+
+pub(super) fn chainid(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    push!(ctx, U256::from(ctx.global_env.chain_id));
+    Status::Continue
+}
+
+pub(super) fn selfbalance(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    push!(ctx, ctx.balance_of(ctx.execution_env.contract_address));
+    Status::Continue
+}
+
+pub(super) fn basefee(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    push!(ctx, ctx.global_env.current_block.base_fee);
+    Status::Continue
+}
+
+pub(super) fn sload(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let key = pop!(ctx);
+    let address = ctx.execution_env.contract_address;
+
+    let is_cold = ctx.access_storage_key(address, key);
+    gas!(ctx, gas::sload_cost(ctx.global_env.spec_id, is_cold));
+
+    push!(ctx, ctx.storage_of(address, key));
+    Status::Continue
+}
+
+pub(super) fn sstore(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    if ctx.execution_env.is_static {
+        halt!(HaltReason::StaticStateChange(OpCode::SSTORE))
+    }
+
+    // EIP-2200 sentry, SSTORE must not be possible with only the call stipend left
+    let spec = ctx.global_env.spec_id;
+    if spec.is_enabled(SpecId::Istanbul) && ctx.gas.remaining() <= gas::CALL_STIPEND {
+        halt!(HaltReason::OutOfGas);
+    }
+
+    let key = pop!(ctx);
+    let value = pop!(ctx);
+    let address = ctx.execution_env.contract_address;
+
+    let current = ctx.storage_of(address, key);
+    let original = *ctx
+        .original_storage
+        .borrow_mut()
+        .entry((address, key))
+        .or_insert(current);
+
+    let is_cold = ctx.access_storage_key(address, key);
+    let (cost, refund) = gas::sstore_cost(spec, original, current, value, is_cold);
+    gas!(ctx, cost);
+    ctx.gas.record_refund(refund);
+    ctx.set_storage(address, key, value);
+
+    Status::Continue
+}
+
+pub(super) fn tload(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let key = pop!(ctx);
+    let address = ctx.execution_env.contract_address;
+
+    let value = ctx
+        .transient_storage
+        .borrow()
+        .get(&(address, key))
+        .copied()
+        .unwrap_or_default();
+
+    push!(ctx, value);
+    Status::Continue
+}
+
+pub(super) fn tstore(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    if ctx.execution_env.is_static {
+        halt!(HaltReason::StaticStateChange(OpCode::TSTORE))
+    }
+
+    let key = pop!(ctx);
+    let value = pop!(ctx);
+    let address = ctx.execution_env.contract_address;
+    ctx.set_transient_storage(address, key, value);
+
+    Status::Continue
+}
+
+/// LOG0 to LOG4
+pub(super) fn log<const N: usize>(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    if ctx.execution_env.is_static {
+        halt!(HaltReason::StaticStateChange(OpCode::LOG(N)))
+    }
+
+    let offset = pop!(ctx);
+    let size = pop!(ctx);
+    let (offset, size) = memory!(ctx, offset, size);
+    gas!(ctx, gas::log_cost(N, size as u64));
+    let data = ctx.execution_machine.memory.read_bytes(offset, size);
+
+    let mut topics = Vec::with_capacity(N);
+    for _ in 0..N {
+        let mut topic = H256::zero();
+        pop!(ctx).to_big_endian(topic.as_bytes_mut());
+        topics.push(topic);
+    }
+
+    ctx.push_log(Log {
+        address: ctx.execution_env.contract_address,
+        topics,
+        data,
+    });
+    Status::Continue
+}
+
+pub(super) fn create(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    attempt!(ctx.create(&OpCode::CREATE));
+    Status::Continue
+}
+
+pub(super) fn create2(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    attempt!(ctx.create(&OpCode::CREATE2));
+    Status::Continue
+}
+
+pub(super) fn call(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    attempt!(ctx.call(&OpCode::CALL));
+    Status::Continue
+}
+
+pub(super) fn callcode(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    attempt!(ctx.call(&OpCode::CALLCODE));
+    Status::Continue
+}
+
+pub(super) fn delegatecall(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    attempt!(ctx.call(&OpCode::DELEGATECALL));
+    Status::Continue
+}
+
+pub(super) fn staticcall(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    attempt!(ctx.call(&OpCode::STATICCALL));
+    Status::Continue
+}
+
+pub(super) fn selfdestruct(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    attempt!(ctx.selfdestruct());
+    Status::Stop
+}
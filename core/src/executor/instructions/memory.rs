@@ -0,0 +1,43 @@
+use ethereum_types::U256;
+
+use super::Status;
+use crate::bytecode::Bytecode;
+use crate::executor::ExecutionContext;
+
+pub(super) fn mload(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let offset = pop!(ctx);
+    let (offset, _) = memory!(ctx, offset, U256::from(32));
+    let word = ctx.execution_machine.memory.read_bytes(offset, 32);
+    push!(ctx, U256::from_big_endian(&word));
+    Status::Continue
+}
+
+pub(super) fn mstore(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let offset = pop!(ctx);
+    let value = pop!(ctx);
+    let (offset, _) = memory!(ctx, offset, U256::from(32));
+
+    let mut value_be = [0u8; 32];
+    value.to_big_endian(&mut value_be);
+
+    ctx.execution_machine
+        .memory
+        .write_bytes(offset, value_be.to_vec());
+    Status::Continue
+}
+
+pub(super) fn mstore8(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let offset = pop!(ctx);
+    let value = pop!(ctx);
+    let (offset, _) = memory!(ctx, offset, U256::one());
+    ctx.execution_machine
+        .memory
+        .write_bytes(offset, vec![value.byte(31)]);
+    Status::Continue
+}
+
+pub(super) fn msize(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let size = ctx.execution_machine.memory.used_capacity();
+    push!(ctx, U256::from(size));
+    Status::Continue
+}
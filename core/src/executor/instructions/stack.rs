@@ -0,0 +1,46 @@
+use ethereum_types::U256;
+
+use super::Status;
+use crate::bytecode::Bytecode;
+use crate::executor::ExecutionContext;
+use crate::halt::HaltReason;
+
+pub(super) fn pop(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    pop!(ctx);
+    Status::Continue
+}
+
+/// PUSH0 to PUSH32, the code is padded so the immediate can always be read
+pub(super) fn push<const N: usize>(ctx: &mut ExecutionContext, code: &Bytecode) -> Status {
+    let start = ctx.execution_machine.pc.get();
+    let Some(immediate) = code.padded().get(start..start + N) else {
+        return Status::Stop;
+    };
+    let value = U256::from_big_endian(immediate);
+
+    push!(ctx, value);
+    ctx.execution_machine.pc.increment_by(N);
+    Status::Continue
+}
+
+pub(super) fn dup<const N: usize>(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let Ok(item) = ctx.execution_machine.stack.get_from_top(N - 1) else {
+        halt!(HaltReason::StackUnderflow);
+    };
+
+    push!(ctx, item);
+    Status::Continue
+}
+
+pub(super) fn swap<const N: usize>(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let stack = &mut ctx.execution_machine.stack;
+    let (Ok(a), Ok(b)) = (stack.get_from_top(0), stack.get_from_top(N)) else {
+        halt!(HaltReason::StackUnderflow);
+    };
+
+    if stack.set_from_top(0, b).is_err() || stack.set_from_top(N, a).is_err() {
+        halt!(HaltReason::StackUnderflow);
+    }
+
+    Status::Continue
+}
@@ -0,0 +1,100 @@
+use ethereum_types::U256;
+
+use super::Status;
+use crate::bytecode::Bytecode;
+use crate::executor::ExecutionContext;
+use crate::gas;
+use crate::halt::HaltReason;
+use crate::utils::{copy_padded, crypto::keccak256};
+
+pub(super) fn sha3(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let offset = pop!(ctx);
+    let size = pop!(ctx);
+    let (offset, size) = memory!(ctx, offset, size);
+    gas!(ctx, gas::sha3_cost(size as u64));
+
+    let value = ctx.execution_machine.memory.read_bytes(offset, size);
+    let hash = keccak256(value);
+
+    push!(ctx, U256::from_big_endian(hash.as_bytes()));
+    Status::Continue
+}
+
+pub(super) fn address(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let address = ctx.execution_env.contract_address;
+    push!(ctx, U256::from(address.as_bytes()));
+    Status::Continue
+}
+
+pub(super) fn caller(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let caller = ctx.execution_env.caller;
+    push!(ctx, U256::from(caller.as_bytes()));
+    Status::Continue
+}
+
+pub(super) fn callvalue(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    push!(ctx, ctx.execution_env.value);
+    Status::Continue
+}
+
+pub(super) fn calldataload(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let offset = pop!(ctx);
+    let data = copy_padded(&ctx.execution_env.calldata, offset, 32);
+
+    push!(ctx, U256::from_big_endian(&data));
+    Status::Continue
+}
+
+pub(super) fn calldatasize(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    push!(ctx, U256::from(ctx.execution_env.calldata.len()));
+    Status::Continue
+}
+
+pub(super) fn codesize(ctx: &mut ExecutionContext, code: &Bytecode) -> Status {
+    push!(ctx, U256::from(code.len()));
+    Status::Continue
+}
+
+pub(super) fn codecopy(ctx: &mut ExecutionContext, code: &Bytecode) -> Status {
+    let dest_offset = pop!(ctx);
+    let offset = pop!(ctx);
+    let size = pop!(ctx);
+    let (dest_offset, size) = memory!(ctx, dest_offset, size);
+    gas!(ctx, gas::copy_cost(size as u64));
+
+    // zero padded past the end of the code
+    let data = code.copy(offset, size);
+    ctx.execution_machine.memory.write_bytes(dest_offset, data);
+    Status::Continue
+}
+
+pub(super) fn returndatasize(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    push!(ctx, U256::from(ctx.return_data.len()));
+    Status::Continue
+}
+
+pub(super) fn returndatacopy(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    let dest_offset = pop!(ctx);
+    let offset = pop!(ctx);
+    let size = pop!(ctx);
+
+    // unlike the other copies, reading past the end is an exceptional halt
+    let end = offset.checked_add(size);
+    if end.is_none_or(|end| end > U256::from(ctx.return_data.len())) {
+        halt!(HaltReason::OutOfOffset)
+    }
+
+    let (dest_offset, size) = memory!(ctx, dest_offset, size);
+    gas!(ctx, gas::copy_cost(size as u64));
+
+    let offset = offset.as_usize();
+    let data = ctx.return_data[offset..(offset + size)].to_vec();
+
+    ctx.execution_machine.memory.write_bytes(dest_offset, data);
+    Status::Continue
+}
+
+pub(super) fn gas(ctx: &mut ExecutionContext, _code: &Bytecode) -> Status {
+    push!(ctx, U256::from(ctx.gas.remaining()));
+    Status::Continue
+}
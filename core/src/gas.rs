@@ -0,0 +1,571 @@
+use color_eyre::owo_colors::OwoColorize;
+use ethereum_types::U256;
+use thiserror::Error;
+
+use crate::operation::OpCode;
+use crate::spec::SpecId;
+
+#[derive(Debug, Error)]
+pub enum GasError {
+    #[error("out of gas")]
+    OutOfGas,
+}
+
+type Result<T> = std::result::Result<T, GasError>;
+
+/// Gas limit used when none is provided, same as the current mainnet block gas limit.
+pub const DEFAULT_GAS_LIMIT: u64 = 30_000_000;
+
+// Fee schedule, names follow Appendix G of the yellow paper https://ethereum.github.io/yellowpaper/paper.pdf
+pub const ZERO: u64 = 0;
+pub const JUMPDEST: u64 = 1;
+pub const BASE: u64 = 2;
+pub const VERYLOW: u64 = 3;
+pub const LOW: u64 = 5;
+pub const MID: u64 = 8;
+pub const HIGH: u64 = 10;
+pub const EXP: u64 = 10;
+pub const EXP_BYTE: u64 = 50;
+pub const SHA3: u64 = 30;
+pub const SHA3_WORD: u64 = 6;
+pub const BLOCKHASH: u64 = 20;
+pub const COPY: u64 = 3;
+pub const LOG: u64 = 375;
+pub const LOG_TOPIC: u64 = 375;
+pub const LOG_DATA: u64 = 8;
+pub const MEMORY: u64 = 3;
+pub const QUAD_COEFF_DIV: u64 = 512;
+pub const COLD_ACCOUNT_ACCESS: u64 = 2600;
+pub const COLD_SLOAD: u64 = 2100;
+pub const WARM_STORAGE_READ: u64 = 100;
+pub const SSTORE_SET: u64 = 20000;
+pub const SSTORE_RESET: u64 = 5000 - COLD_SLOAD;
+pub const SSTORE_CLEARS_SCHEDULE: i64 = 4800;
+pub const CREATE: u64 = 32000;
+pub const CODE_DEPOSIT: u64 = 200;
+pub const INITCODE_WORD: u64 = 2;
+pub const CALL_VALUE: u64 = 9000;
+pub const NEW_ACCOUNT: u64 = 25000;
+pub const CALL_STIPEND: u64 = 2300;
+pub const SELFDESTRUCT: u64 = 5000;
+pub const SELFDESTRUCT_REFUND: i64 = 24000;
+pub const MAX_REFUND_QUOTIENT: u64 = 5;
+
+// Costs that were changed by later forks, suffixed by the fork that introduced them
+pub const EXP_BYTE_FRONTIER: u64 = 10;
+pub const SLOAD_FRONTIER: u64 = 50;
+pub const SLOAD_TANGERINE: u64 = 200;
+pub const SLOAD_ISTANBUL: u64 = 800;
+pub const BALANCE_FRONTIER: u64 = 20;
+pub const BALANCE_TANGERINE: u64 = 400;
+pub const BALANCE_ISTANBUL: u64 = 700;
+pub const EXTCODE_FRONTIER: u64 = 20;
+pub const EXTCODE_TANGERINE: u64 = 700;
+pub const EXTCODEHASH_CONSTANTINOPLE: u64 = 400;
+pub const EXTCODEHASH_ISTANBUL: u64 = 700;
+pub const CALL_FRONTIER: u64 = 40;
+pub const CALL_TANGERINE: u64 = 700;
+pub const SSTORE_RESET_FRONTIER: u64 = 5000;
+pub const SSTORE_CLEARS_SCHEDULE_FRONTIER: i64 = 15000;
+pub const MAX_REFUND_QUOTIENT_FRONTIER: u64 = 2;
+
+#[derive(Debug, Default, Clone)]
+pub struct Gas {
+    limit: u64,
+    remaining: u64,
+    // can go negative within a call frame, see EIP-2200
+    refunded: i64,
+}
+
+impl Gas {
+    pub fn new(limit: u64) -> Self {
+        Self {
+            limit,
+            remaining: limit,
+            refunded: 0,
+        }
+    }
+
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    pub fn remaining(&self) -> u64 {
+        self.remaining
+    }
+
+    pub fn used(&self) -> u64 {
+        self.limit - self.remaining
+    }
+
+    /// return error if there is not enough gas left to pay for `cost`
+    pub fn record_cost(&mut self, cost: u64) -> Result<()> {
+        self.remaining = self.remaining.checked_sub(cost).ok_or(GasError::OutOfGas)?;
+        Ok(())
+    }
+
+    /// exceptional halts burn everything that is left
+    pub fn consume_all(&mut self) {
+        self.remaining = 0;
+    }
+
+    /// give back gas that was charged but not spent, eg. what is left by a call
+    pub fn erase_cost(&mut self, returned: u64) {
+        self.remaining += returned;
+    }
+
+    /// drop the refunds, they are not given back when the execution reverts
+    pub fn clear_refund(&mut self) {
+        self.refunded = 0;
+    }
+
+    pub fn record_refund(&mut self, refund: i64) {
+        self.refunded += refund;
+    }
+
+    pub fn refunded(&self) -> i64 {
+        self.refunded
+    }
+
+    /// The refund actually given back at the end of the transaction, capped to half
+    /// of the gas used and to a fifth since EIP-3529
+    pub fn final_refund(&self, spec: SpecId) -> u64 {
+        let quotient = if spec.is_enabled(SpecId::London) {
+            MAX_REFUND_QUOTIENT
+        } else {
+            MAX_REFUND_QUOTIENT_FRONTIER
+        };
+
+        let refunded = self.refunded.max(0) as u64;
+        refunded.min(self.used() / quotient)
+    }
+}
+
+impl std::fmt::Display for Gas {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            r"{}
+limit: {}
+used: {}
+remaining: {}
+refunded: {}
+",
+            "[ Gas ]".purple(),
+            self.limit,
+            self.used(),
+            self.remaining,
+            self.refunded
+        )
+    }
+}
+
+/// Number of 32-byte words needed to hold `size` bytes
+pub fn num_words(size: u64) -> u64 {
+    size.div_ceil(32)
+}
+
+/// The total cost of having `words` words of memory allocated
+///
+/// https://www.evm.codes/about#memoryexpansion
+pub fn memory_cost(words: u64) -> u64 {
+    MEMORY
+        .saturating_mul(words)
+        .saturating_add(words.saturating_mul(words) / QUAD_COEFF_DIV)
+}
+
+/// The cost of growing memory from `current_words` to `new_words`
+pub fn memory_expansion_cost(current_words: u64, new_words: u64) -> u64 {
+    if new_words <= current_words {
+        return 0;
+    }
+
+    memory_cost(new_words) - memory_cost(current_words)
+}
+
+/// Dynamic cost of EXP, paid per byte of the exponent, raised by EIP-160
+pub fn exp_cost(spec: SpecId, exponent: U256) -> u64 {
+    let byte_len = (exponent.bits() as u64).div_ceil(8);
+
+    if spec.is_enabled(SpecId::SpuriousDragon) {
+        EXP_BYTE * byte_len
+    } else {
+        EXP_BYTE_FRONTIER * byte_len
+    }
+}
+
+/// Dynamic cost of SHA3, paid per word hashed
+pub fn sha3_cost(size: u64) -> u64 {
+    SHA3_WORD.saturating_mul(num_words(size))
+}
+
+/// Dynamic cost of the *COPY operations, paid per word copied
+pub fn copy_cost(size: u64) -> u64 {
+    COPY.saturating_mul(num_words(size))
+}
+
+/// Dynamic cost of CREATE and CREATE2 for the init code, see EIP-3860
+pub fn initcode_cost(size: u64) -> u64 {
+    INITCODE_WORD.saturating_mul(num_words(size))
+}
+
+/// Dynamic cost of LOG0..LOG4, paid per topic and per byte of data
+pub fn log_cost(topics: usize, size: u64) -> u64 {
+    LOG_TOPIC * topics as u64 + LOG_DATA.saturating_mul(size)
+}
+
+/// Cost of accessing an account with `op`, which only depends on the account being warm
+/// since EIP-2929. Before that it was a flat cost per operation, raised by EIP-150 and EIP-1884.
+pub fn account_access_cost(spec: SpecId, op: &OpCode, is_cold: bool) -> u64 {
+    if spec.is_enabled(SpecId::Berlin) {
+        return if is_cold {
+            COLD_ACCOUNT_ACCESS
+        } else {
+            WARM_STORAGE_READ
+        };
+    }
+
+    let istanbul = spec.is_enabled(SpecId::Istanbul);
+    let tangerine = spec.is_enabled(SpecId::TangerineWhistle);
+
+    match op {
+        OpCode::BALANCE if istanbul => BALANCE_ISTANBUL,
+        OpCode::BALANCE if tangerine => BALANCE_TANGERINE,
+        OpCode::BALANCE => BALANCE_FRONTIER,
+
+        OpCode::EXTCODEHASH if istanbul => EXTCODEHASH_ISTANBUL,
+        OpCode::EXTCODEHASH => EXTCODEHASH_CONSTANTINOPLE,
+
+        OpCode::EXTCODESIZE | OpCode::EXTCODECOPY if tangerine => EXTCODE_TANGERINE,
+        OpCode::EXTCODESIZE | OpCode::EXTCODECOPY => EXTCODE_FRONTIER,
+
+        _ if tangerine => CALL_TANGERINE,
+        _ => CALL_FRONTIER,
+    }
+}
+
+/// Gas actually given to a call, all but one 64th of what is `available` at most since EIP-150.
+/// Before that, requesting more than what is available runs out of gas.
+pub fn call_gas(spec: SpecId, available: u64, requested: U256) -> u64 {
+    if !spec.is_enabled(SpecId::TangerineWhistle) {
+        return requested.min(U256::from(u64::MAX)).as_u64();
+    }
+
+    let max = available - available / 64;
+
+    if requested > U256::from(max) {
+        max
+    } else {
+        requested.as_u64()
+    }
+}
+
+/// Cost of SELFDESTRUCT, `creates_account` if the balance is sent to a new account
+pub fn selfdestruct_cost(spec: SpecId, is_cold: bool, creates_account: bool) -> u64 {
+    // free until EIP-150
+    if !spec.is_enabled(SpecId::TangerineWhistle) {
+        return ZERO;
+    }
+
+    let mut cost = SELFDESTRUCT;
+
+    if creates_account {
+        cost += NEW_ACCOUNT;
+    }
+
+    if is_cold && spec.is_enabled(SpecId::Berlin) {
+        cost += COLD_ACCOUNT_ACCESS;
+    }
+
+    cost
+}
+
+/// Refund of SELFDESTRUCT, removed by EIP-3529
+pub fn selfdestruct_refund(spec: SpecId) -> i64 {
+    if spec.is_enabled(SpecId::London) {
+        0
+    } else {
+        SELFDESTRUCT_REFUND
+    }
+}
+
+/// Cost of SLOAD, which only depends on the slot being warm since EIP-2929
+pub fn sload_cost(spec: SpecId, is_cold: bool) -> u64 {
+    if spec.is_enabled(SpecId::Berlin) {
+        if is_cold {
+            COLD_SLOAD
+        } else {
+            WARM_STORAGE_READ
+        }
+    } else if spec.is_enabled(SpecId::Istanbul) {
+        SLOAD_ISTANBUL
+    } else if spec.is_enabled(SpecId::TangerineWhistle) {
+        SLOAD_TANGERINE
+    } else {
+        SLOAD_FRONTIER
+    }
+}
+
+/// Cost and refund of SSTORE. Net gas metering was introduced by EIP-1283 in Constantinople,
+/// removed by Petersburg, and came back with EIP-2200 in Istanbul. Its costs then changed
+/// with EIP-2929 and its refunds were reduced by EIP-3529.
+///
+/// `original` is the value of the slot at the start of the transaction
+pub fn sstore_cost(
+    spec: SpecId,
+    original: U256,
+    current: U256,
+    new: U256,
+    is_cold: bool,
+) -> (u64, i64) {
+    let schedule = if spec.is_enabled(SpecId::London) {
+        (WARM_STORAGE_READ, SSTORE_RESET, SSTORE_CLEARS_SCHEDULE)
+    } else if spec.is_enabled(SpecId::Berlin) {
+        (
+            WARM_STORAGE_READ,
+            SSTORE_RESET,
+            SSTORE_CLEARS_SCHEDULE_FRONTIER,
+        )
+    } else if spec.is_enabled(SpecId::Istanbul) {
+        (
+            SLOAD_ISTANBUL,
+            SSTORE_RESET_FRONTIER,
+            SSTORE_CLEARS_SCHEDULE_FRONTIER,
+        )
+    } else if spec == SpecId::Constantinople {
+        (
+            SLOAD_TANGERINE,
+            SSTORE_RESET_FRONTIER,
+            SSTORE_CLEARS_SCHEDULE_FRONTIER,
+        )
+    } else {
+        return legacy_sstore_cost(current, new);
+    };
+
+    let (cost, refund) = net_sstore_cost(schedule, original, current, new);
+
+    if is_cold && spec.is_enabled(SpecId::Berlin) {
+        (cost + COLD_SLOAD, refund)
+    } else {
+        (cost, refund)
+    }
+}
+
+/// Setting a slot costs more than resetting it, and clearing it is refunded
+fn legacy_sstore_cost(current: U256, new: U256) -> (u64, i64) {
+    if current.is_zero() && !new.is_zero() {
+        (SSTORE_SET, 0)
+    } else if !current.is_zero() && new.is_zero() {
+        (SSTORE_RESET_FRONTIER, SSTORE_CLEARS_SCHEDULE_FRONTIER)
+    } else {
+        (SSTORE_RESET_FRONTIER, 0)
+    }
+}
+
+/// Only the first change of a slot in a transaction pays in full, `schedule` being
+/// the cost of an SLOAD, the cost of resetting a slot and the refund for clearing it
+fn net_sstore_cost(
+    (sload, reset, clears): (u64, u64, i64),
+    original: U256,
+    current: U256,
+    new: U256,
+) -> (u64, i64) {
+    if current == new {
+        return (sload, 0);
+    }
+
+    if original == current {
+        if original.is_zero() {
+            return (SSTORE_SET, 0);
+        }
+
+        let refund = if new.is_zero() { clears } else { 0 };
+
+        return (reset, refund);
+    }
+
+    let mut refund = 0;
+
+    if !original.is_zero() {
+        if current.is_zero() {
+            refund -= clears;
+        } else if new.is_zero() {
+            refund += clears;
+        }
+    }
+
+    if original == new {
+        if original.is_zero() {
+            refund += (SSTORE_SET - sload) as i64;
+        } else {
+            refund += (reset - sload) as i64;
+        }
+    }
+
+    (sload, refund)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_cost_until_out_of_gas() {
+        let mut gas = Gas::new(10);
+        gas.record_cost(7).unwrap();
+        assert_eq!(gas.remaining(), 3);
+        assert_eq!(gas.used(), 7);
+        assert!(gas.record_cost(4).is_err());
+        assert_eq!(gas.remaining(), 3);
+    }
+
+    #[test]
+    fn memory_expansion_is_quadratic() {
+        assert_eq!(memory_expansion_cost(0, 1), 3);
+        assert_eq!(memory_expansion_cost(1, 1), 0);
+        assert_eq!(memory_expansion_cost(0, 32), 98);
+        assert_eq!(memory_expansion_cost(0, 1024), 5120);
+        assert_eq!(memory_expansion_cost(32, 1024), 5120 - 98);
+    }
+
+    #[test]
+    fn sstore_net_gas_metering() {
+        let (zero, one, two) = (U256::zero(), U256::one(), U256::from(2));
+        let sstore_cost = |original, current, new, is_cold| {
+            super::sstore_cost(SpecId::London, original, current, new, is_cold)
+        };
+
+        // (original, current, new) => (cost, refund)
+        assert_eq!(sstore_cost(zero, zero, zero, false), (100, 0));
+        assert_eq!(sstore_cost(zero, zero, one, false), (20000, 0));
+        assert_eq!(sstore_cost(zero, one, zero, false), (100, 19900));
+        assert_eq!(sstore_cost(zero, one, two, false), (100, 0));
+        assert_eq!(sstore_cost(one, one, zero, false), (2900, 4800));
+        assert_eq!(sstore_cost(one, one, two, false), (2900, 0));
+        assert_eq!(sstore_cost(one, zero, one, false), (100, -4800 + 2800));
+        assert_eq!(sstore_cost(one, zero, two, false), (100, -4800));
+        assert_eq!(sstore_cost(one, two, zero, false), (100, 4800));
+        assert_eq!(sstore_cost(one, two, one, false), (100, 2800));
+
+        assert_eq!(sstore_cost(zero, zero, zero, true), (2200, 0));
+        assert_eq!(sstore_cost(one, one, zero, true), (5000, 4800));
+    }
+
+    #[test]
+    fn sstore_cost_per_fork() {
+        let (zero, one, two) = (U256::zero(), U256::one(), U256::from(2));
+
+        // no net gas metering, only the current value matters
+        assert_eq!(
+            sstore_cost(SpecId::Petersburg, one, two, zero, false),
+            (5000, 15000)
+        );
+        assert_eq!(
+            sstore_cost(SpecId::Petersburg, zero, zero, one, false),
+            (20000, 0)
+        );
+        assert_eq!(
+            sstore_cost(SpecId::Petersburg, zero, one, two, true),
+            (5000, 0)
+        );
+
+        // EIP-1283
+        assert_eq!(
+            sstore_cost(SpecId::Constantinople, zero, one, two, false),
+            (200, 0)
+        );
+        assert_eq!(
+            sstore_cost(SpecId::Constantinople, zero, one, zero, false),
+            (200, 19800)
+        );
+
+        // EIP-2200
+        assert_eq!(
+            sstore_cost(SpecId::Istanbul, one, one, zero, false),
+            (5000, 15000)
+        );
+        assert_eq!(
+            sstore_cost(SpecId::Istanbul, one, two, one, false),
+            (800, 4200)
+        );
+
+        // EIP-2929 before EIP-3529
+        assert_eq!(
+            sstore_cost(SpecId::Berlin, one, one, zero, true),
+            (5000, 15000)
+        );
+    }
+
+    #[test]
+    fn access_cost_per_fork() {
+        let cost = |spec, op| account_access_cost(spec, &op, true);
+
+        assert_eq!(cost(SpecId::Frontier, OpCode::BALANCE), 20);
+        assert_eq!(cost(SpecId::TangerineWhistle, OpCode::BALANCE), 400);
+        assert_eq!(cost(SpecId::Istanbul, OpCode::BALANCE), 700);
+        assert_eq!(cost(SpecId::Homestead, OpCode::CALL), 40);
+        assert_eq!(cost(SpecId::Petersburg, OpCode::EXTCODECOPY), 700);
+        assert_eq!(cost(SpecId::Petersburg, OpCode::EXTCODEHASH), 400);
+        assert_eq!(cost(SpecId::Berlin, OpCode::EXTCODEHASH), 2600);
+        assert_eq!(
+            account_access_cost(SpecId::Berlin, &OpCode::CALL, false),
+            100
+        );
+
+        assert_eq!(sload_cost(SpecId::Frontier, true), 50);
+        assert_eq!(sload_cost(SpecId::Byzantium, true), 200);
+        assert_eq!(sload_cost(SpecId::Istanbul, true), 800);
+        assert_eq!(sload_cost(SpecId::Cancun, true), 2100);
+    }
+
+    #[test]
+    fn final_refund_is_capped_to_a_fifth_of_gas_used() {
+        let mut gas = Gas::new(100_000);
+        gas.record_cost(10_000).unwrap();
+        gas.record_refund(4800);
+        assert_eq!(gas.final_refund(SpecId::London), 2000);
+
+        gas.record_refund(-4000);
+        assert_eq!(gas.final_refund(SpecId::London), 800);
+    }
+
+    #[test]
+    fn final_refund_is_capped_to_half_of_gas_used_before_london() {
+        let mut gas = Gas::new(100_000);
+        gas.record_cost(10_000).unwrap();
+        gas.record_refund(24000);
+        assert_eq!(gas.final_refund(SpecId::Berlin), 5000);
+        assert_eq!(gas.final_refund(SpecId::London), 2000);
+    }
+
+    #[test]
+    fn call_gas_keeps_one_64th() {
+        assert_eq!(call_gas(SpecId::Cancun, 6400, U256::from(1000)), 1000);
+        assert_eq!(call_gas(SpecId::Cancun, 6400, U256::from(6400)), 6300);
+        assert_eq!(call_gas(SpecId::Cancun, 6400, U256::MAX), 6300);
+
+        // all of it can be requested, or more, before EIP-150
+        assert_eq!(call_gas(SpecId::Homestead, 6400, U256::from(6400)), 6400);
+        assert_eq!(call_gas(SpecId::Homestead, 6400, U256::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn exp_cost_per_exponent_byte() {
+        assert_eq!(exp_cost(SpecId::Cancun, U256::zero()), 0);
+        assert_eq!(exp_cost(SpecId::Cancun, U256::from(0xff)), 50);
+        assert_eq!(exp_cost(SpecId::Cancun, U256::from(0x100)), 100);
+        assert_eq!(exp_cost(SpecId::Cancun, U256::MAX), 50 * 32);
+        assert_eq!(exp_cost(SpecId::Homestead, U256::MAX), 10 * 32);
+    }
+
+    #[test]
+    fn selfdestruct_cost_per_fork() {
+        assert_eq!(selfdestruct_cost(SpecId::Homestead, true, true), 0);
+        assert_eq!(selfdestruct_cost(SpecId::Istanbul, true, true), 30000);
+        assert_eq!(selfdestruct_cost(SpecId::Berlin, true, false), 7600);
+        assert_eq!(selfdestruct_cost(SpecId::Cancun, false, false), 5000);
+
+        assert_eq!(selfdestruct_refund(SpecId::Berlin), 24000);
+        assert_eq!(selfdestruct_refund(SpecId::London), 0);
+    }
+}
@@ -0,0 +1,61 @@
+use evm_components::StackError;
+use thiserror::Error;
+
+use crate::gas::GasError;
+use crate::operation::OpCode;
+use crate::precompile::PrecompileError;
+
+/// Why a frame stopped exceptionally, consuming all of its gas and reverting its changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum HaltReason {
+    #[error("out of gas")]
+    OutOfGas,
+    #[error("stack underflow")]
+    StackUnderflow,
+    #[error("stack overflow")]
+    StackOverflow,
+    #[error("JUMP destination must be a JUMPDEST instruction")]
+    InvalidJump,
+    /// the designated INVALID operation, 0xFE
+    #[error("designated invalid operation 0xfe")]
+    InvalidOpcode,
+    #[error("undefined operation {0:#04x}")]
+    UndefinedOpcode(u8),
+    #[error("operation {0} is not enabled at this hardfork")]
+    NotActivated(OpCode),
+    /// defined by the protocol but not implemented by this interpreter
+    #[error("unsupported operation {0}")]
+    NotSupported(OpCode),
+    #[error("state modification in static context : {0}")]
+    StaticStateChange(OpCode),
+    /// a frame nested deeper than `MAX_CALL_DEPTH`, a CALL or CREATE past the limit
+    /// only fails without halting the caller
+    #[error("call depth exceeded")]
+    CallDepthExceeded,
+    /// RETURNDATACOPY past the end of the return data
+    #[error("RETURNDATACOPY reads past the end of the return data")]
+    OutOfOffset,
+    #[error("init code is bigger than the EIP-3860 limit")]
+    InitCodeSizeExceeded,
+    #[error("contract code is bigger than the EIP-170 limit")]
+    CodeSizeExceeded,
+    #[error("contract code must not start with 0xEF, see EIP-3541")]
+    InvalidCodePrefix,
+    #[error("precompile failed : {0}")]
+    Precompile(#[from] PrecompileError),
+}
+
+impl From<GasError> for HaltReason {
+    fn from(_: GasError) -> Self {
+        Self::OutOfGas
+    }
+}
+
+impl From<StackError> for HaltReason {
+    fn from(error: StackError) -> Self {
+        match error {
+            StackError::StackOverflow => Self::StackOverflow,
+            StackError::StackUnderflow | StackError::IndexOutOfBounds => Self::StackUnderflow,
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! Two's complement signed integers on top of `U256`, as used by the signed opcodes
+
+use std::cmp::Ordering;
+
+use ethereum_types::U256;
+
+/// The smallest signed value, -2^255, which has no positive counterpart
+pub const MIN: U256 = U256([0, 0, 0, 0x8000_0000_0000_0000]);
+
+pub fn is_negative(value: U256) -> bool {
+    value.bit(255)
+}
+
+/// -`value`, wrapping around for `MIN`
+pub fn negate(value: U256) -> U256 {
+    (!value).overflowing_add(U256::one()).0
+}
+
+/// Magnitude of `value`, `MIN` stays `MIN` as 2^255 does not fit in a signed value
+pub fn abs(value: U256) -> U256 {
+    if is_negative(value) {
+        negate(value)
+    } else {
+        value
+    }
+}
+
+pub fn cmp(a: U256, b: U256) -> Ordering {
+    match (is_negative(a), is_negative(b)) {
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        // same sign, two's complement keeps the order
+        _ => a.cmp(&b),
+    }
+}
+
+/// SDIV, rounding toward zero. Dividing by zero gives zero and `MIN / -1` overflows to `MIN`.
+pub fn div(a: U256, b: U256) -> U256 {
+    if b.is_zero() {
+        return U256::zero();
+    }
+
+    let quotient = abs(a) / abs(b);
+
+    if is_negative(a) != is_negative(b) {
+        negate(quotient)
+    } else {
+        quotient
+    }
+}
+
+/// SMOD, the result takes the sign of `a`. Zero if `b` is zero.
+pub fn rem(a: U256, b: U256) -> U256 {
+    if b.is_zero() {
+        return U256::zero();
+    }
+
+    let remainder = abs(a) % abs(b);
+
+    if is_negative(a) {
+        negate(remainder)
+    } else {
+        remainder
+    }
+}
+
+/// SAR, shifts right while keeping the sign
+pub fn sar(value: U256, shift: U256) -> U256 {
+    let negative = is_negative(value);
+
+    if shift >= U256::from(256) {
+        return if negative { U256::MAX } else { U256::zero() };
+    }
+
+    if negative {
+        !(!value >> shift)
+    } else {
+        value >> shift
+    }
+}
+
+/// SIGNEXTEND, extends the sign of the `byte_index + 1` lowest bytes of `value` to all 32 bytes
+pub fn sign_extend(byte_index: U256, value: U256) -> U256 {
+    if byte_index >= U256::from(31) {
+        return value;
+    }
+
+    let sign_bit = byte_index.as_usize() * 8 + 7;
+    let mask = (U256::one() << sign_bit) - 1;
+
+    if value.bit(sign_bit) {
+        value | !mask
+    } else {
+        value & mask
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn int(value: i64) -> U256 {
+        if value < 0 {
+            negate(U256::from(value.unsigned_abs()))
+        } else {
+            U256::from(value)
+        }
+    }
+
+    const MINUS_ONE: U256 = U256::MAX;
+
+    #[test]
+    fn negation_and_sign() {
+        assert_eq!(negate(int(1)), MINUS_ONE);
+        assert_eq!(negate(MINUS_ONE), int(1));
+        assert_eq!(negate(U256::zero()), U256::zero());
+        assert_eq!(negate(MIN), MIN);
+
+        assert!(is_negative(MINUS_ONE));
+        assert!(is_negative(MIN));
+        assert!(!is_negative(U256::zero()));
+        assert!(!is_negative(MIN - 1));
+
+        assert_eq!(abs(int(-5)), int(5));
+        assert_eq!(abs(int(5)), int(5));
+        assert_eq!(abs(MIN), MIN);
+    }
+
+    #[test]
+    fn signed_comparison() {
+        let ordered = [MIN, MIN + 1, int(-2), MINUS_ONE, int(0), int(1), MIN - 1];
+
+        for (i, a) in ordered.iter().enumerate() {
+            for (j, b) in ordered.iter().enumerate() {
+                assert_eq!(cmp(*a, *b), i.cmp(&j), "{a:x} {b:x}");
+            }
+        }
+    }
+
+    #[test]
+    fn signed_division() {
+        let cases = [
+            (int(10), int(3), int(3)),
+            (int(-10), int(3), int(-3)),
+            (int(10), int(-3), int(-3)),
+            (int(-10), int(-3), int(3)),
+            (int(-1), int(2), int(0)),
+            (int(7), int(0), int(0)),
+            (int(-7), int(0), int(0)),
+            (MIN, MINUS_ONE, MIN),
+            (MIN, int(1), MIN),
+            (MIN, MIN, int(1)),
+            (MIN - 1, MINUS_ONE, MIN + 1),
+            (MIN, int(2), negate(U256::one() << 254)),
+        ];
+
+        for (a, b, expected) in cases {
+            assert_eq!(div(a, b), expected, "{a:x} / {b:x}");
+        }
+    }
+
+    #[test]
+    fn signed_modulo() {
+        let cases = [
+            (int(10), int(3), int(1)),
+            (int(-10), int(3), int(-1)),
+            (int(10), int(-3), int(1)),
+            (int(-10), int(-3), int(-1)),
+            (int(-9), int(3), int(0)),
+            (int(7), int(0), int(0)),
+            (int(-7), int(0), int(0)),
+            (MIN, MINUS_ONE, int(0)),
+            (MIN, int(3), int(-2)),
+            (MIN - 1, MIN, MIN - 1),
+        ];
+
+        for (a, b, expected) in cases {
+            assert_eq!(rem(a, b), expected, "{a:x} % {b:x}");
+        }
+    }
+
+    #[test]
+    fn arithmetic_shift_right() {
+        let cases = [
+            (int(16), int(2), int(4)),
+            (int(-16), int(2), int(-4)),
+            (int(-1), int(1), int(-1)),
+            (int(-3), int(1), int(-2)),
+            (MIN, int(255), MINUS_ONE),
+            (MIN, int(254), int(-2)),
+            (MIN - 1, int(254), int(1)),
+            (int(1), int(0), int(1)),
+            (MIN, int(0), MIN),
+            (MIN, int(256), MINUS_ONE),
+            (MIN - 1, int(256), int(0)),
+            (MINUS_ONE, U256::MAX, MINUS_ONE),
+            (int(1), U256::MAX, int(0)),
+        ];
+
+        for (value, shift, expected) in cases {
+            assert_eq!(sar(value, shift), expected, "{value:x} >> {shift:x}");
+        }
+    }
+
+    #[test]
+    fn sign_extension() {
+        let cases = [
+            (int(0), U256::from(0xff), MINUS_ONE),
+            (int(0), U256::from(0x7f), int(0x7f)),
+            (int(0), U256::from(0x80), int(-128)),
+            (int(0), U256::from(0x1ff), MINUS_ONE),
+            (int(0), U256::from(0x17f), int(0x7f)),
+            (int(1), U256::from(0x8000), int(-32768)),
+            (int(1), U256::from(0xff7fff), int(0x7fff)),
+            (int(30), MIN - 1, MINUS_ONE),
+            (int(30), MIN, int(0)),
+            (int(30), U256::one() << 247, negate(U256::one() << 247)),
+            (int(31), int(0x80), int(0x80)),
+            (int(31), MIN, MIN),
+            (U256::MAX, int(0xff), int(0xff)),
+        ];
+
+        for (byte_index, value, expected) in cases {
+            assert_eq!(
+                sign_extend(byte_index, value),
+                expected,
+                "{byte_index:x} {value:x}"
+            );
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use std::{cell::RefCell, rc::Rc};
+
+use ethereum_types::{H160, U256};
+
+use crate::environment::ExecutionStatus;
+use crate::executor::{ExecutionContext, ExecutionEnvironment};
+use crate::halt::HaltReason;
+use crate::log::Log;
+use crate::operation::OpCode;
+
+/// An inspector shared by every frame of a transaction, the caller keeps a clone
+/// to read what it collected once the execution is over
+pub type SharedInspector = Rc<RefCell<dyn Inspector>>;
+
+/// How a nested frame ended, as seen by the frame that entered it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameEnd<'a> {
+    pub status: ExecutionStatus,
+    /// returned data, or the revert data
+    pub output: &'a [u8],
+    /// gas left to the frame, given back to its caller unless it halted
+    pub gas_remaining: u64,
+}
+
+/// Hooks called by `ExecutionContext` while it runs, they all do nothing by default
+#[allow(unused_variables)]
+pub trait Inspector {
+    /// Before `opcode` is executed, the PC still points at it
+    fn step(&mut self, ctx: &ExecutionContext, opcode: OpCode) {}
+
+    /// After `opcode` was executed, or failed with `halt`
+    fn step_end(&mut self, ctx: &ExecutionContext, opcode: OpCode, halt: Option<HaltReason>) {}
+
+    /// Before a frame is entered by CALL, CALLCODE, DELEGATECALL or STATICCALL,
+    /// the code of `code_address` runs in `env`
+    fn call(&mut self, kind: OpCode, code_address: H160, env: &ExecutionEnvironment) {}
+
+    fn call_end(&mut self, end: &FrameEnd) {}
+
+    /// Before the init code of a CREATE or CREATE2 runs, `env.contract_address` is
+    /// the address of the new contract
+    fn create(&mut self, kind: OpCode, init_code: &[u8], env: &ExecutionEnvironment) {}
+
+    fn create_end(&mut self, end: &FrameEnd) {}
+
+    fn log(&mut self, log: &Log) {}
+
+    /// `address` sends its whole `balance` to `beneficiary`
+    fn selfdestruct(&mut self, address: H160, beneficiary: H160, balance: U256) {}
+}
@@ -0,0 +1,179 @@
+use ethereum_types::{H160, U256};
+
+use crate::bytecode::Bytecode;
+
+/// A single change made to the state, holding what is needed to undo it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JournalEntry {
+    AccountCreated {
+        address: H160,
+    },
+    BalanceChanged {
+        address: H160,
+        previous: U256,
+    },
+    NonceChanged {
+        address: H160,
+        previous: u64,
+    },
+    CodeChanged {
+        address: H160,
+        previous: Option<Bytecode>,
+    },
+    StorageChanged {
+        address: H160,
+        key: U256,
+        previous: U256,
+    },
+    TransientStorageChanged {
+        address: H160,
+        key: U256,
+        previous: U256,
+    },
+    AddressWarmed {
+        address: H160,
+    },
+    StorageKeyWarmed {
+        address: H160,
+        key: U256,
+    },
+    LogPushed,
+    ContractCreated {
+        address: H160,
+    },
+    SelfDestructed {
+        address: H160,
+    },
+}
+
+/// Position in the journal that a call frame goes back to if it reverts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint {
+    len: usize,
+    /// number of checkpoints still open when this one was taken
+    depth: usize,
+}
+
+/// Every change made to the state during a transaction, in order
+#[derive(Debug, Default)]
+pub struct Journal {
+    entries: Vec<JournalEntry>,
+    depth: usize,
+}
+
+impl Journal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, entry: JournalEntry) {
+        self.entries.push(entry);
+    }
+
+    pub fn checkpoint(&mut self) -> Checkpoint {
+        let checkpoint = Checkpoint {
+            len: self.entries.len(),
+            depth: self.depth,
+        };
+        self.depth += 1;
+        checkpoint
+    }
+
+    /// Keeps the changes made since `checkpoint`, they can still be undone if an
+    /// enclosing frame reverts. Committing the outermost checkpoint makes them final.
+    pub fn commit(&mut self, checkpoint: Checkpoint) {
+        self.depth = checkpoint.depth;
+
+        if self.depth == 0 {
+            self.entries.clear();
+        }
+    }
+
+    /// Removes the changes made since `checkpoint` and returns them, most recent first,
+    /// so that they can be undone
+    pub fn revert(&mut self, checkpoint: Checkpoint) -> Vec<JournalEntry> {
+        self.depth = checkpoint.depth;
+
+        let mut reverted = self.entries.split_off(checkpoint.len);
+        reverted.reverse();
+        reverted
+    }
+
+    pub fn entries(&self) -> &[JournalEntry] {
+        &self.entries
+    }
+
+    /// Forgets everything, at the end of a transaction
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.depth = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn balance_changed(previous: u64) -> JournalEntry {
+        JournalEntry::BalanceChanged {
+            address: H160::zero(),
+            previous: U256::from(previous),
+        }
+    }
+
+    #[test]
+    fn revert_returns_most_recent_changes_first() {
+        let mut journal = Journal::new();
+        journal.record(balance_changed(0));
+
+        let checkpoint = journal.checkpoint();
+        journal.record(balance_changed(1));
+        journal.record(balance_changed(2));
+
+        assert_eq!(
+            journal.revert(checkpoint),
+            vec![balance_changed(2), balance_changed(1)]
+        );
+        assert_eq!(journal.entries(), &[balance_changed(0)]);
+    }
+
+    #[test]
+    fn committed_changes_are_reverted_with_enclosing_frame() {
+        let mut journal = Journal::new();
+        let outer = journal.checkpoint();
+        journal.record(balance_changed(0));
+
+        let inner = journal.checkpoint();
+        journal.record(balance_changed(1));
+        journal.commit(inner);
+        assert_eq!(journal.entries().len(), 2);
+
+        assert_eq!(journal.revert(outer).len(), 2);
+        assert!(journal.entries().is_empty());
+    }
+
+    #[test]
+    fn committing_outermost_checkpoint_clears_journal() {
+        let mut journal = Journal::new();
+        let checkpoint = journal.checkpoint();
+        journal.record(JournalEntry::LogPushed);
+        journal.commit(checkpoint);
+
+        assert!(journal.entries().is_empty());
+    }
+
+    #[test]
+    fn committing_nested_checkpoint_keeps_changes() {
+        let mut journal = Journal::new();
+        let outer = journal.checkpoint();
+
+        // taken before anything was recorded, like the outer one
+        let inner = journal.checkpoint();
+        journal.record(JournalEntry::LogPushed);
+        journal.commit(inner);
+        assert_eq!(journal.entries(), &[JournalEntry::LogPushed]);
+
+        journal.commit(outer);
+        assert!(journal.entries().is_empty());
+    }
+}
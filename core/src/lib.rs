@@ -1,5 +1,19 @@
+pub mod access;
+pub mod analysis;
+pub mod bytecode;
+pub mod chain;
+pub mod diff;
 pub mod environment;
 pub mod evm;
 pub mod executor;
+pub mod gas;
+pub mod halt;
+pub mod i256;
+pub mod inspector;
+pub mod journal;
+pub mod log;
 pub mod operation;
+pub mod precompile;
+pub mod spec;
+pub mod tracer;
 pub mod utils;
@@ -0,0 +1,30 @@
+use ethereum_types::{H160, H256};
+
+/// An event emitted by LOG0 to LOG4
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Log {
+    /// the contract that emitted it
+    pub address: H160,
+    /// at most four, the first one usually identifies the event
+    pub topics: Vec<H256>,
+    pub data: Vec<u8>,
+}
+
+impl std::fmt::Display for Log {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{:?}", self.address)?;
+
+        for (index, topic) in self.topics.iter().enumerate() {
+            writeln!(f, "  topic {index}: {topic:?}")?;
+        }
+
+        writeln!(
+            f,
+            "  data: 0x{}",
+            self.data
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect::<String>()
+        )
+    }
+}
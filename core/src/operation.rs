@@ -1,271 +1,371 @@
 use core::fmt;
 
-use thiserror::Error;
-
-#[derive(Debug, Error)]
-pub enum OperationError {
-    #[error("unsupported operation {0}")]
-    InvalidOperation(u8),
-    #[error("expect PUSH operation followed by a value : {0}")]
-    PushValueExpected(OpCode),
-    #[error("JUMP destination must be a JUMPDEST instruction")]
-    JumpDestExpected,
+use crate::gas;
+use crate::spec::SpecId;
+
+/// What the table knows about an operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpCodeInfo {
+    pub name: &'static str,
+    /// bytes of immediate data following the operation, only PUSH has some
+    pub immediate: u8,
+    /// items popped from the stack
+    pub inputs: u8,
+    /// items pushed on the stack
+    pub outputs: u8,
+    /// the static part of the cost, the dynamic part is charged by the executor
+    pub gas: u64,
+    /// the hardfork that introduced the operation, it is undefined before
+    pub introduced_in: SpecId,
 }
 
-#[derive(Debug)]
-pub enum OpCode {
+const PUSH_NAMES: [&str; 33] = [
+    "PUSH0", "PUSH1", "PUSH2", "PUSH3", "PUSH4", "PUSH5", "PUSH6", "PUSH7", "PUSH8", "PUSH9",
+    "PUSH10", "PUSH11", "PUSH12", "PUSH13", "PUSH14", "PUSH15", "PUSH16", "PUSH17", "PUSH18",
+    "PUSH19", "PUSH20", "PUSH21", "PUSH22", "PUSH23", "PUSH24", "PUSH25", "PUSH26", "PUSH27",
+    "PUSH28", "PUSH29", "PUSH30", "PUSH31", "PUSH32",
+];
+
+const DUP_NAMES: [&str; 16] = [
+    "DUP1", "DUP2", "DUP3", "DUP4", "DUP5", "DUP6", "DUP7", "DUP8", "DUP9", "DUP10", "DUP11",
+    "DUP12", "DUP13", "DUP14", "DUP15", "DUP16",
+];
+
+const SWAP_NAMES: [&str; 16] = [
+    "SWAP1", "SWAP2", "SWAP3", "SWAP4", "SWAP5", "SWAP6", "SWAP7", "SWAP8", "SWAP9", "SWAP10",
+    "SWAP11", "SWAP12", "SWAP13", "SWAP14", "SWAP15", "SWAP16",
+];
+
+const LOG_NAMES: [&str; 5] = ["LOG0", "LOG1", "LOG2", "LOG3", "LOG4"];
+
+const fn push(n: usize) -> OpCodeInfo {
+    OpCodeInfo {
+        name: PUSH_NAMES[n],
+        immediate: n as u8,
+        inputs: 0,
+        outputs: 1,
+        gas: if n == 0 { gas::BASE } else { gas::VERYLOW },
+        introduced_in: if n == 0 {
+            SpecId::Shanghai
+        } else {
+            SpecId::Frontier
+        },
+    }
+}
+
+const fn dup(n: usize) -> OpCodeInfo {
+    family(DUP_NAMES[n - 1], n as u8, n as u8 + 1, gas::VERYLOW)
+}
+
+const fn swap(n: usize) -> OpCodeInfo {
+    family(SWAP_NAMES[n - 1], n as u8 + 1, n as u8 + 1, gas::VERYLOW)
+}
+
+const fn log(n: usize) -> OpCodeInfo {
+    family(LOG_NAMES[n], n as u8 + 2, 0, gas::LOG)
+}
+
+const fn family(name: &'static str, inputs: u8, outputs: u8, gas: u64) -> OpCodeInfo {
+    OpCodeInfo {
+        name,
+        immediate: 0,
+        inputs,
+        outputs,
+        gas,
+        introduced_in: SpecId::Frontier,
+    }
+}
+
+/// Generates `OpCode`, the `OPCODES` table and both conversions between bytes and
+/// operations from a single list, so that they cannot disagree. Families of
+/// operations cover a range of bytes, numbered from the given first index.
+macro_rules! opcodes {
+    (@since) => { SpecId::Frontier };
+    (@since $since:ident) => { SpecId::$since };
+
+    (
+        $( $byte:literal => $op:ident($inputs:literal, $outputs:literal, $gas:expr $(, $since:ident)?), )*
+        ;
+        $( $low:literal..=$high:literal => $family:ident($first:literal, $info:ident), )*
+    ) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum OpCode {
+            $( $op, )*
+            $( $family(usize), )*
+            /// A byte that is not assigned to any operation
+            UNDEFINED(u8),
+        }
+
+        /// Every byte and the operation it encodes, `None` for the undefined ones
+        pub static OPCODES: [Option<OpCodeInfo>; 256] = {
+            let mut table = [None; 256];
+
+            $(
+                table[$byte] = Some(OpCodeInfo {
+                    name: stringify!($op),
+                    immediate: 0,
+                    inputs: $inputs,
+                    outputs: $outputs,
+                    gas: $gas,
+                    introduced_in: opcodes!(@since $($since)?),
+                });
+            )*
+
+            $(
+                let mut byte = $low;
+                while byte <= $high {
+                    table[byte] = Some($info(byte - $low + $first));
+                    byte += 1;
+                }
+            )*
+
+            table
+        };
+
+        impl OpCode {
+            /// The byte encoding the operation
+            pub fn byte(&self) -> u8 {
+                match *self {
+                    $( Self::$op => $byte, )*
+                    $( Self::$family(n) => ($low + n - $first) as u8, )*
+                    Self::UNDEFINED(byte) => byte,
+                }
+            }
+        }
+
+        impl From<u8> for OpCode {
+            fn from(value: u8) -> Self {
+                match value {
+                    $( $byte => Self::$op, )*
+                    $( $low..=$high => Self::$family((value - $low) as usize + $first), )*
+                    _ => Self::UNDEFINED(value),
+                }
+            }
+        }
+    };
+}
+
+opcodes! {
     // Stop and Arithmetic Operations
-    STOP,
-    ADD,
-    MUL,
-    SUB,
-    DIV,
-    SDIV,
-    MOD,
-    SMOD,
-    ADDMOD,
-    MULMOD,
-    EXP,
+    0x00 => STOP(0, 0, gas::ZERO),
+    0x01 => ADD(2, 1, gas::VERYLOW),
+    0x02 => MUL(2, 1, gas::LOW),
+    0x03 => SUB(2, 1, gas::VERYLOW),
+    0x04 => DIV(2, 1, gas::LOW),
+    0x05 => SDIV(2, 1, gas::LOW),
+    0x06 => MOD(2, 1, gas::LOW),
+    0x07 => SMOD(2, 1, gas::LOW),
+    0x08 => ADDMOD(3, 1, gas::MID),
+    0x09 => MULMOD(3, 1, gas::MID),
+    0x0A => EXP(2, 1, gas::EXP),
+    0x0B => SIGNEXTEND(2, 1, gas::LOW),
     // Comparison & Bitwise Logic Operations
-    LT,
-    GT,
-    SLT,
-    SGT,
-    EQ,
-    ISZERO,
-    AND,
-    OR,
-    XOR,
-    NOT,
-    BYTE,
-    SHL,
-    SHR,
+    0x10 => LT(2, 1, gas::VERYLOW),
+    0x11 => GT(2, 1, gas::VERYLOW),
+    0x12 => SLT(2, 1, gas::VERYLOW),
+    0x13 => SGT(2, 1, gas::VERYLOW),
+    0x14 => EQ(2, 1, gas::VERYLOW),
+    0x15 => ISZERO(1, 1, gas::VERYLOW),
+    0x16 => AND(2, 1, gas::VERYLOW),
+    0x17 => OR(2, 1, gas::VERYLOW),
+    0x18 => XOR(2, 1, gas::VERYLOW),
+    0x19 => NOT(1, 1, gas::VERYLOW),
+    0x1A => BYTE(2, 1, gas::VERYLOW),
+    0x1B => SHL(2, 1, gas::VERYLOW, Constantinople),
+    0x1C => SHR(2, 1, gas::VERYLOW, Constantinople),
+    0x1D => SAR(2, 1, gas::VERYLOW, Constantinople),
     // SHA3
-    SHA3,
+    0x20 => SHA3(2, 1, gas::SHA3),
     // Environmental Information
-    ADDRESS,
-    BALANCE,
-    CALLER,
-    CALLVALUE,
-    CODESIZE,
-    CODECOPY,
-    CALLDATALOAD,
-    CALLDATASIZE,
+    0x30 => ADDRESS(0, 1, gas::BASE),
+    0x31 => BALANCE(1, 1, gas::ZERO),
+    0x32 => ORIGIN(0, 1, gas::BASE),
+    0x33 => CALLER(0, 1, gas::BASE),
+    0x34 => CALLVALUE(0, 1, gas::BASE),
+    0x35 => CALLDATALOAD(1, 1, gas::VERYLOW),
+    0x36 => CALLDATASIZE(0, 1, gas::BASE),
+    0x37 => CALLDATACOPY(3, 0, gas::VERYLOW),
+    0x38 => CODESIZE(0, 1, gas::BASE),
+    0x39 => CODECOPY(3, 0, gas::VERYLOW),
+    0x3A => GASPRICE(0, 1, gas::BASE),
+    0x3B => EXTCODESIZE(1, 1, gas::ZERO),
+    0x3C => EXTCODECOPY(4, 0, gas::ZERO),
+    0x3D => RETURNDATASIZE(0, 1, gas::BASE, Byzantium),
+    0x3E => RETURNDATACOPY(3, 0, gas::VERYLOW, Byzantium),
+    0x3F => EXTCODEHASH(1, 1, gas::ZERO, Constantinople),
     // Block Information
-    SELFBALANCE,
+    0x40 => BLOCKHASH(1, 1, gas::BLOCKHASH),
+    0x41 => COINBASE(0, 1, gas::BASE),
+    0x42 => TIMESTAMP(0, 1, gas::BASE),
+    0x43 => NUMBER(0, 1, gas::BASE),
+    0x44 => PREVRANDAO(0, 1, gas::BASE),
+    0x45 => GASLIMIT(0, 1, gas::BASE),
+    0x46 => CHAINID(0, 1, gas::BASE, Istanbul),
+    0x47 => SELFBALANCE(0, 1, gas::LOW, Istanbul),
+    0x48 => BASEFEE(0, 1, gas::BASE, London),
+    0x49 => BLOBHASH(1, 1, gas::VERYLOW, Cancun),
+    0x4A => BLOBBASEFEE(0, 1, gas::BASE, Cancun),
     // Stack Memory Storage and Flow Operations
-    POP,
-    MLOAD,
-    MSTORE,
-    MSTORE8,
-    JUMP,
-    JUMPI,
-    JUMPDEST,
-    PC,
-    MSIZE,
-    // Push Operations
-    PUSH(usize),
-    // Duplication Operations
-    DUP(usize),
-    // Exchange Operations
-    SWAP(usize),
-    // LOG0,
-    LOG(usize),
-    RETURN,
-
-    INVALID,
+    0x50 => POP(1, 0, gas::BASE),
+    0x51 => MLOAD(1, 1, gas::VERYLOW),
+    0x52 => MSTORE(2, 0, gas::VERYLOW),
+    0x53 => MSTORE8(2, 0, gas::VERYLOW),
+    0x54 => SLOAD(1, 1, gas::ZERO),
+    0x55 => SSTORE(2, 0, gas::ZERO),
+    0x56 => JUMP(1, 0, gas::MID),
+    0x57 => JUMPI(2, 0, gas::HIGH),
+    0x58 => PC(0, 1, gas::BASE),
+    0x59 => MSIZE(0, 1, gas::BASE),
+    0x5A => GAS(0, 1, gas::BASE),
+    0x5B => JUMPDEST(0, 0, gas::JUMPDEST),
+    0x5C => TLOAD(1, 1, gas::WARM_STORAGE_READ, Cancun),
+    0x5D => TSTORE(2, 0, gas::WARM_STORAGE_READ, Cancun),
+    0x5E => MCOPY(3, 0, gas::VERYLOW, Cancun),
+    // System Operations
+    0xF0 => CREATE(3, 1, gas::CREATE),
+    0xF1 => CALL(7, 1, gas::ZERO),
+    0xF2 => CALLCODE(7, 1, gas::ZERO),
+    0xF3 => RETURN(2, 0, gas::ZERO),
+    0xF4 => DELEGATECALL(6, 1, gas::ZERO, Homestead),
+    0xF5 => CREATE2(4, 1, gas::CREATE, Constantinople),
+    0xFA => STATICCALL(6, 1, gas::ZERO, Byzantium),
+    0xFD => REVERT(2, 0, gas::ZERO, Byzantium),
+    0xFE => INVALID(0, 0, gas::ZERO),
+    0xFF => SELFDESTRUCT(1, 0, gas::ZERO),
+    ;
+    // Push, Duplication, Exchange and Logging Operations
+    0x5F..=0x7F => PUSH(0, push),
+    0x80..=0x8F => DUP(1, dup),
+    0x90..=0x9F => SWAP(1, swap),
+    0xA0..=0xA4 => LOG(0, log),
 }
 
-impl From<u8> for OpCode {
-    fn from(value: u8) -> Self {
-        match value {
-            0x00 => Self::STOP,
-            0x01 => Self::ADD,
-            0x02 => Self::MUL,
-            0x03 => Self::SUB,
-            0x04 => Self::DIV,
-            0x05 => Self::SDIV,
-            0x06 => Self::MOD,
-            0x07 => Self::SMOD,
-            0x08 => Self::ADDMOD,
-            0x09 => Self::MULMOD,
-            0x0A => Self::EXP,
-
-            0x10 => Self::LT,
-            0x11 => Self::GT,
-            0x12 => Self::SLT,
-            0x13 => Self::SGT,
-            0x14 => Self::EQ,
-            0x15 => Self::ISZERO,
-            0x16 => Self::AND,
-            0x17 => Self::OR,
-            0x18 => Self::XOR,
-            0x19 => Self::NOT,
-            0x1A => Self::BYTE,
-            0x1B => Self::SHR,
-            0x1C => Self::SHL,
-
-            0x20 => Self::SHA3,
-
-            0x30 => Self::ADDRESS,
-            0x31 => Self::BALANCE,
-            0x33 => Self::CALLER,
-            0x34 => Self::CALLVALUE,
-            0x38 => Self::CODESIZE,
-            0x39 => Self::CODECOPY,
-            0x35 => Self::CALLDATALOAD,
-            0x36 => Self::CALLDATASIZE,
-
-            0x47 => Self::SELFBALANCE,
-
-            0x50 => Self::POP,
-            0x51 => Self::MLOAD,
-            0x52 => Self::MSTORE,
-            0x53 => Self::MSTORE8,
-            0x56 => Self::JUMP,
-            0x57 => Self::JUMPI,
-            0x58 => Self::PC,
-            0x59 => Self::MSIZE,
-
-            0x5B => Self::JUMPDEST,
-
-            0x60..=0x7F => Self::PUSH((value - 0x5F) as usize),
-
-            0x80..=0x8F => Self::DUP((value - 0x7F) as usize),
-
-            0x90..=0x9F => Self::SWAP((value - 0x8F) as usize),
-            // 0xA0 => Self::LOG0,
-            0xA0..=0xA4 => Self::LOG((value - 0xA0) as usize),
-
-            0xF3 => Self::RETURN,
-
-            0xFE => Self::INVALID,
-            _ => Self::INVALID,
-        }
+impl OpCode {
+    /// The entry of the operation in the table, `None` if it is undefined
+    pub fn info(&self) -> Option<&'static OpCodeInfo> {
+        OPCODES[self.byte() as usize].as_ref()
+    }
+
+    /// The hardfork that introduced the operation, it is invalid before
+    pub fn introduced_in(&self) -> SpecId {
+        self.info()
+            .map_or(SpecId::Frontier, |info| info.introduced_in)
     }
 }
 
 impl fmt::Display for OpCode {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            match self {
-                Self::STOP => "STOP",
-                Self::POP => "POP",
-                Self::ADD => "ADD",
-                Self::SUB => "SUB",
-                Self::MUL => "MUL",
-                Self::DIV => "DIV",
-                Self::SDIV => "SDIV",
-                Self::MOD => "MOD",
-                Self::SMOD => "SMOD",
-                Self::ADDMOD => "ADDMOD",
-                Self::MULMOD => "MULMOD",
-                Self::EXP => "EXP",
-
-                Self::LT => "LT",
-                Self::GT => "GT",
-                Self::SLT => "SLT",
-                Self::SGT => "SGT",
-                Self::EQ => "EQ",
-                Self::ISZERO => "ISZERO",
-                Self::AND => "AND",
-                Self::OR => "OR",
-                Self::XOR => "XOR",
-                Self::NOT => "NOT",
-                Self::BYTE => "BYTE",
-                Self::SHL => "SHL",
-                Self::SHR => "SHR",
-
-                Self::SHA3 => "SHA3",
-
-                Self::ADDRESS => "ADDRESS",
-                Self::BALANCE => "BALANCE",
-                Self::CALLER => "CALLER",
-                Self::CODESIZE => "CODESIZE",
-                Self::CODECOPY => "CODECOPY",
-                Self::CALLDATALOAD => "CALLDATALOAD",
-                Self::CALLDATASIZE => "CALLDATASIZE",
-
-                Self::SELFBALANCE => "SELFBALANCE",
-
-                Self::MLOAD => "MLOAD",
-                Self::MSTORE => "MSTORE",
-                Self::MSTORE8 => "MSTORE8",
-                Self::JUMP => "JUMP",
-                Self::JUMPI => "JUMPI",
-                Self::PC => "PC",
-                Self::MSIZE => "MSIZE",
-                Self::JUMPDEST => "JUMPDEST",
-
-                Self::PUSH(amount) => Box::leak(Box::new(format!("PUSH{}", amount))),
-
-                Self::DUP(amount) => Box::leak(Box::new(format!("DUP{}", amount))),
-
-                Self::SWAP(amount) => Box::leak(Box::new(format!("SWAP{}", amount))),
-
-                Self::LOG(amount) => Box::leak(Box::new(format!("LOG{}", amount))),
-
-                Self::CALLVALUE => "CALLVALUE",
-                Self::RETURN => "RETURN",
-
-                Self::INVALID => "INVALID",
-            }
-        )
+        match self.info() {
+            Some(info) => write!(f, "{}", info.name),
+            None => write!(f, "UNDEFINED({:#04x})", self.byte()),
+        }
     }
 }
 
-#[macro_export]
-macro_rules! construct_push_op {
-    ($a:expr, $self:expr, $program:expr) => {{
-        let start = $self.execution_machine.pc.get() + 1;
-        let end = start + ($a - 1);
-        let value = &$program[start..=end];
-
-        $self
-            .execution_machine
-            .stack
-            .push(U256::from_big_endian(value))
-            .unwrap();
-        $self.execution_machine.pc.increment_by(1 + $a);
-    }};
-}
+/// One operation per line with its offset and immediate data, eg. `0002: PUSH1 0x40`
+pub fn disassemble(code: &[u8]) -> String {
+    let mut lines = Vec::new();
+    let mut offset = 0;
+
+    while let Some(&byte) = code.get(offset) {
+        let opcode = OpCode::from(byte);
+        let immediate = opcode.info().map_or(0, |info| info.immediate as usize);
+        let data = code
+            .iter()
+            .skip(offset + 1)
+            .take(immediate)
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>();
+
+        if immediate == 0 {
+            lines.push(format!("{offset:04x}: {opcode}"));
+        } else {
+            lines.push(format!("{offset:04x}: {opcode} 0x{data}"));
+        }
 
-#[macro_export]
-macro_rules! construct_dup_op {
-    ($a:expr, $self:expr) => {{
-        let item = $self.execution_machine.stack.get_from_top($a - 1)?;
-        $self.execution_machine.stack.push(item)?;
-        $self.execution_machine.pc.increment_by(1);
-    }};
-}
+        offset += 1 + immediate;
+    }
 
-#[macro_export]
-macro_rules! construct_swap_op {
-    ($a:expr, $self:expr) => {{
-        let a = $self.execution_machine.stack.get_from_top(0)?;
-        let b = $self.execution_machine.stack.get_from_top($a)?;
-        $self.execution_machine.stack.set_from_top(0, b)?;
-        $self.execution_machine.stack.set_from_top(1, a)?;
-        $self.execution_machine.pc.increment_by(1);
-    }};
+    lines.join("\n")
 }
 
-#[macro_export]
-macro_rules! construct_log_op {
-    ($a:expr, $self:expr) => {{
-        let _ = $self.execution_machine.stack.pop()?;
-        let _ = $self.execution_machine.stack.pop()?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_agrees_with_decoder() {
+        for byte in 0..=u8::MAX {
+            let opcode = OpCode::from(byte);
+
+            assert_eq!(opcode.byte(), byte);
+            assert_eq!(
+                opcode.info().is_none(),
+                matches!(opcode, OpCode::UNDEFINED(_)),
+                "{byte:#04x}"
+            );
+        }
+
+        assert_eq!(OPCODES.iter().flatten().count(), 149);
+    }
 
-        let mut log: Vec<U256> = Vec::new();
+    #[test]
+    fn every_byte_round_trips_through_the_table() {
+        let mut seen = Vec::new();
 
-        for _ in 0..$a {
-            let topic = $self.execution_machine.stack.pop()?;
-            log.push(topic);
+        for (byte, info) in OPCODES.iter().enumerate() {
+            let opcode = OpCode::from(byte as u8);
+
+            assert_eq!(opcode.byte() as usize, byte);
+            assert_eq!(opcode.info(), info.as_ref());
+            assert_eq!(OpCode::from(opcode.byte()), opcode);
+            assert!(!seen.contains(&opcode), "{byte:#04x}");
+
+            if let Some(info) = info {
+                assert_eq!(opcode.to_string(), info.name);
+            }
+
+            seen.push(opcode);
         }
+    }
 
-        $self.logs.borrow_mut().push(log);
-        $self.execution_machine.pc.increment_by(1);
-    }};
+    #[test]
+    fn operation_names() {
+        assert_eq!(OpCode::from(0x1B).to_string(), "SHL");
+        assert_eq!(OpCode::from(0x5F).to_string(), "PUSH0");
+        assert_eq!(OpCode::from(0x7F).to_string(), "PUSH32");
+        assert_eq!(OpCode::from(0x8F).to_string(), "DUP16");
+        assert_eq!(OpCode::from(0x90).to_string(), "SWAP1");
+        assert_eq!(OpCode::from(0xA4).to_string(), "LOG4");
+        assert_eq!(OpCode::from(0xFE).to_string(), "INVALID");
+        assert_eq!(OpCode::from(0x0C).to_string(), "UNDEFINED(0x0c)");
+    }
+
+    #[test]
+    fn stack_items_and_immediates() {
+        let info = |byte: u8| OPCODES[byte as usize].unwrap();
+
+        assert_eq!((info(0xF1).inputs, info(0xF1).outputs), (7, 1));
+        assert_eq!((info(0x8F).inputs, info(0x8F).outputs), (16, 17));
+        assert_eq!((info(0x9F).inputs, info(0x9F).outputs), (17, 17));
+        assert_eq!((info(0xA2).inputs, info(0xA2).outputs), (4, 0));
+        assert_eq!(info(0x60).immediate, 1);
+        assert_eq!(info(0x7F).immediate, 32);
+        assert_eq!(info(0x5F).immediate, 0);
+        assert_eq!(info(0x5F).introduced_in, SpecId::Shanghai);
+    }
+
+    #[test]
+    fn disassemble_code() {
+        // PUSH1 0x80 PUSH1 0x40 MSTORE undefined INVALID truncated PUSH3
+        assert_eq!(
+            disassemble(&[0x60, 0x80, 0x60, 0x40, 0x52, 0x0C, 0xFE, 0x62, 0x01]),
+            "0000: PUSH1 0x80\n\
+             0002: PUSH1 0x40\n\
+             0004: MSTORE\n\
+             0005: UNDEFINED(0x0c)\n\
+             0006: INVALID\n\
+             0007: PUSH3 0x01"
+        );
+    }
 }
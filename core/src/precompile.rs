@@ -0,0 +1,142 @@
+use std::collections::BTreeMap;
+
+use ethereum_types::H160;
+use thiserror::Error;
+
+use crate::spec::SpecId;
+
+pub mod blake2f;
+pub mod bn254;
+pub mod ecrecover;
+pub mod hash;
+pub mod identity;
+pub mod kzg;
+pub mod modexp;
+
+#[derive(Debug, Clone, Copy, Error, PartialEq, Eq)]
+pub enum PrecompileError {
+    #[error("out of gas")]
+    OutOfGas,
+    #[error("invalid precompile input : {0}")]
+    InvalidInput(&'static str),
+}
+
+/// Gas used and data returned by a precompile that succeeded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrecompileOutput {
+    pub gas_used: u64,
+    pub data: Vec<u8>,
+}
+
+impl PrecompileOutput {
+    pub fn new(gas_used: u64, data: Vec<u8>) -> Self {
+        Self { gas_used, data }
+    }
+}
+
+/// Any error consumes all the gas given to the precompile, like an exceptional halt
+pub type PrecompileResult = Result<PrecompileOutput, PrecompileError>;
+
+/// A precompile is run with the call data, the gas given to the call and the
+/// hardfork, which some of them are priced by
+pub type Precompile = fn(&[u8], u64, SpecId) -> PrecompileResult;
+
+/// Contracts implemented natively, consulted by the call path before looking for code
+#[derive(Debug, Clone, Default)]
+pub struct Precompiles {
+    precompiles: BTreeMap<H160, Precompile>,
+}
+
+impl Precompiles {
+    /// The precompiles available at the `spec` hardfork
+    pub fn new(spec: SpecId) -> Self {
+        let mut precompiles = Self::default();
+
+        precompiles.insert(H160::from_low_u64_be(0x01), ecrecover::run);
+        precompiles.insert(H160::from_low_u64_be(0x02), hash::sha256);
+        precompiles.insert(H160::from_low_u64_be(0x03), hash::ripemd160);
+        precompiles.insert(H160::from_low_u64_be(0x04), identity::run);
+
+        if spec.is_enabled(SpecId::Byzantium) {
+            precompiles.insert(H160::from_low_u64_be(0x05), modexp::run);
+            precompiles.insert(H160::from_low_u64_be(0x06), bn254::add);
+            precompiles.insert(H160::from_low_u64_be(0x07), bn254::mul);
+            precompiles.insert(H160::from_low_u64_be(0x08), bn254::pairing);
+        }
+
+        if spec.is_enabled(SpecId::Istanbul) {
+            precompiles.insert(H160::from_low_u64_be(0x09), blake2f::run);
+        }
+
+        if spec.is_enabled(SpecId::Cancun) {
+            precompiles.insert(H160::from_low_u64_be(0x0a), kzg::point_evaluation);
+        }
+
+        precompiles
+    }
+
+    pub fn insert(&mut self, address: H160, precompile: Precompile) {
+        self.precompiles.insert(address, precompile);
+    }
+
+    pub fn get(&self, address: &H160) -> Option<Precompile> {
+        self.precompiles.get(address).copied()
+    }
+
+    pub fn contains(&self, address: &H160) -> bool {
+        self.precompiles.contains_key(address)
+    }
+
+    pub fn addresses(&self) -> impl Iterator<Item = &H160> {
+        self.precompiles.keys()
+    }
+}
+
+/// Fails if `cost` is more than the gas given to the precompile
+fn ensure_gas(cost: u64, gas_limit: u64) -> Result<(), PrecompileError> {
+    if cost > gas_limit {
+        Err(PrecompileError::OutOfGas)
+    } else {
+        Ok(())
+    }
+}
+
+/// `input` padded with zeros, or truncated, to exactly `len` bytes
+fn right_pad(input: &[u8], len: usize) -> Vec<u8> {
+    let mut padded = vec![0u8; len];
+    let copied = input.len().min(len);
+    padded[..copied].copy_from_slice(&input[..copied]);
+    padded
+}
+
+/// Decodes hex test vectors
+#[cfg(test)]
+fn decode(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn precompiles_depend_on_hardfork() {
+        let address = |i| H160::from_low_u64_be(i);
+
+        assert_eq!(Precompiles::new(SpecId::Homestead).addresses().count(), 4);
+        assert!(!Precompiles::new(SpecId::Petersburg).contains(&address(0x09)));
+        assert!(Precompiles::new(SpecId::Istanbul).contains(&address(0x09)));
+        assert!(!Precompiles::new(SpecId::Shanghai).contains(&address(0x0a)));
+        assert_eq!(Precompiles::new(SpecId::Cancun).addresses().count(), 10);
+        assert!(!Precompiles::new(SpecId::Cancun).contains(&address(0x0b)));
+    }
+
+    #[test]
+    fn input_is_padded_or_truncated() {
+        assert_eq!(right_pad(&[1, 2], 4), vec![1, 2, 0, 0]);
+        assert_eq!(right_pad(&[1, 2, 3], 2), vec![1, 2]);
+    }
+}
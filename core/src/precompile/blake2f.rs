@@ -0,0 +1,193 @@
+use super::{ensure_gas, PrecompileError, PrecompileOutput, PrecompileResult};
+use crate::spec::SpecId;
+
+pub const BLAKE2F_PER_ROUND: u64 = 1;
+
+/// rounds (4), h (64), m (128), t (16) and f (1)
+const INPUT_LEN: usize = 213;
+
+const IV: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+const SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+pub fn cost(rounds: u32) -> u64 {
+    BLAKE2F_PER_ROUND * rounds as u64
+}
+
+/// The mixing function G
+fn mix(v: &mut [u64; 16], a: usize, b: usize, c: usize, d: usize, x: u64, y: u64) {
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+    v[d] = (v[d] ^ v[a]).rotate_right(32);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(24);
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(63);
+}
+
+/// The BLAKE2b compression function F with a configurable number of rounds
+pub fn compress(rounds: u32, h: &mut [u64; 8], m: [u64; 16], t: [u64; 2], f: bool) {
+    let mut v = [0u64; 16];
+    v[..8].copy_from_slice(h);
+    v[8..].copy_from_slice(&IV);
+
+    v[12] ^= t[0];
+    v[13] ^= t[1];
+
+    if f {
+        v[14] = !v[14];
+    }
+
+    for i in 0..rounds as usize {
+        let s = &SIGMA[i % 10];
+
+        mix(&mut v, 0, 4, 8, 12, m[s[0]], m[s[1]]);
+        mix(&mut v, 1, 5, 9, 13, m[s[2]], m[s[3]]);
+        mix(&mut v, 2, 6, 10, 14, m[s[4]], m[s[5]]);
+        mix(&mut v, 3, 7, 11, 15, m[s[6]], m[s[7]]);
+        mix(&mut v, 0, 5, 10, 15, m[s[8]], m[s[9]]);
+        mix(&mut v, 1, 6, 11, 12, m[s[10]], m[s[11]]);
+        mix(&mut v, 2, 7, 8, 13, m[s[12]], m[s[13]]);
+        mix(&mut v, 3, 4, 9, 14, m[s[14]], m[s[15]]);
+    }
+
+    for i in 0..8 {
+        h[i] ^= v[i] ^ v[i + 8];
+    }
+}
+
+fn read_words<const N: usize>(input: &[u8]) -> [u64; N] {
+    let mut words = [0u64; N];
+
+    for (word, bytes) in words.iter_mut().zip(input.chunks_exact(8)) {
+        *word = u64::from_le_bytes(bytes.try_into().unwrap());
+    }
+
+    words
+}
+
+/// 0x09, the BLAKE2b F compression function, see EIP-152
+pub fn run(input: &[u8], gas_limit: u64, _spec: SpecId) -> PrecompileResult {
+    if input.len() != INPUT_LEN {
+        return Err(PrecompileError::InvalidInput(
+            "blake2f input must be 213 bytes",
+        ));
+    }
+
+    let rounds = u32::from_be_bytes(input[..4].try_into().unwrap());
+    let cost = cost(rounds);
+    ensure_gas(cost, gas_limit)?;
+
+    let f = match input[212] {
+        0 => false,
+        1 => true,
+        _ => {
+            return Err(PrecompileError::InvalidInput(
+                "blake2f final flag must be 0 or 1",
+            ))
+        }
+    };
+
+    let mut h = read_words::<8>(&input[4..68]);
+    let m = read_words::<16>(&input[68..196]);
+    let t = read_words::<2>(&input[196..212]);
+
+    compress(rounds, &mut h, m, t, f);
+
+    Ok(PrecompileOutput::new(
+        cost,
+        h.iter().flat_map(|word| word.to_le_bytes()).collect(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::decode;
+    use super::*;
+
+    // test vector 5 of EIP-152, which is BLAKE2b-512 of "abc"
+    const INPUT: &str = concat!(
+        "0000000c",
+        "48c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5",
+        "d182e6ad7f520e511f6c3e2b8c68059b6bbd41fbabd9831f79217e1319cde05b",
+        "6162630000000000000000000000000000000000000000000000000000000000",
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "03000000000000000000000000000000",
+        "01"
+    );
+
+    #[test]
+    fn blake2f_vectors() {
+        let output = run(&decode(INPUT), 12, SpecId::Cancun).unwrap();
+
+        assert_eq!(output.gas_used, 12);
+        assert_eq!(
+            output.data,
+            decode(concat!(
+                "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1",
+                "7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923"
+            ))
+        );
+
+        // non final block
+        let mut input = decode(INPUT);
+        input[212] = 0;
+        assert_eq!(
+            run(&input, 12, SpecId::Cancun).unwrap().data,
+            decode(concat!(
+                "75ab69d3190a562c51aef8d88f1c2775876944407270c42c9844252c26d28752",
+                "98743e7f6d5ea2f2d3e8d226039cd31b4e426ac4f2d3d666a610c2116fde4735"
+            ))
+        );
+
+        // zero rounds is free
+        let mut input = decode(INPUT);
+        input[3] = 0;
+        assert_eq!(run(&input, 0, SpecId::Cancun).unwrap().gas_used, 0);
+
+        assert_eq!(
+            run(&decode(INPUT), 11, SpecId::Cancun),
+            Err(PrecompileError::OutOfGas)
+        );
+    }
+
+    #[test]
+    fn blake2f_invalid_input() {
+        let input = decode(INPUT);
+
+        assert!(matches!(
+            run(&input[..212], 12, SpecId::Cancun),
+            Err(PrecompileError::InvalidInput(_))
+        ));
+
+        let mut input = decode(INPUT);
+        input[212] = 2;
+        assert!(matches!(
+            run(&input, 12, SpecId::Cancun),
+            Err(PrecompileError::InvalidInput(_))
+        ));
+    }
+}
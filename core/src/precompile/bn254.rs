@@ -0,0 +1,261 @@
+use bn::{pairing_batch, AffineG1, AffineG2, Fq, Fq2, Fr, Group, Gt, G1, G2};
+
+use super::{ensure_gas, right_pad, PrecompileError, PrecompileOutput, PrecompileResult};
+use crate::spec::SpecId;
+
+pub const ADD: u64 = 150;
+pub const MUL: u64 = 6000;
+pub const PAIRING_BASE: u64 = 45000;
+pub const PAIRING_PER_POINT: u64 = 34000;
+
+/// Costs before EIP-1108 lowered them in Istanbul
+pub const ADD_BYZANTIUM: u64 = 500;
+pub const MUL_BYZANTIUM: u64 = 40000;
+pub const PAIRING_BASE_BYZANTIUM: u64 = 100000;
+pub const PAIRING_PER_POINT_BYZANTIUM: u64 = 80000;
+
+/// A G1 point followed by a G2 point
+const PAIRING_ELEMENT_LEN: usize = 192;
+
+pub fn add_cost(spec: SpecId) -> u64 {
+    if spec.is_enabled(SpecId::Istanbul) {
+        ADD
+    } else {
+        ADD_BYZANTIUM
+    }
+}
+
+pub fn mul_cost(spec: SpecId) -> u64 {
+    if spec.is_enabled(SpecId::Istanbul) {
+        MUL
+    } else {
+        MUL_BYZANTIUM
+    }
+}
+
+pub fn pairing_cost(spec: SpecId, pairs: usize) -> u64 {
+    if spec.is_enabled(SpecId::Istanbul) {
+        PAIRING_BASE + PAIRING_PER_POINT * pairs as u64
+    } else {
+        PAIRING_BASE_BYZANTIUM + PAIRING_PER_POINT_BYZANTIUM * pairs as u64
+    }
+}
+
+fn read_fq(input: &[u8]) -> Result<Fq, PrecompileError> {
+    Fq::from_slice(&input[..32])
+        .map_err(|_| PrecompileError::InvalidInput("field element not lower than modulus"))
+}
+
+/// A point as `x, y`, where `(0, 0)` is the point at infinity
+fn read_g1(input: &[u8]) -> Result<G1, PrecompileError> {
+    let x = read_fq(&input[..32])?;
+    let y = read_fq(&input[32..64])?;
+
+    if x.is_zero() && y.is_zero() {
+        return Ok(G1::zero());
+    }
+
+    AffineG1::new(x, y)
+        .map(Into::into)
+        .map_err(|_| PrecompileError::InvalidInput("invalid G1 point"))
+}
+
+/// A point as `x_im, x_re, y_im, y_re`, where all zeros is the point at infinity
+fn read_g2(input: &[u8]) -> Result<G2, PrecompileError> {
+    let x = Fq2::new(read_fq(&input[32..64])?, read_fq(&input[..32])?);
+    let y = Fq2::new(read_fq(&input[96..128])?, read_fq(&input[64..96])?);
+
+    if x.is_zero() && y.is_zero() {
+        return Ok(G2::zero());
+    }
+
+    AffineG2::new(x, y)
+        .map(Into::into)
+        .map_err(|_| PrecompileError::InvalidInput("invalid G2 point"))
+}
+
+fn encode_g1(point: G1) -> Vec<u8> {
+    let mut output = vec![0u8; 64];
+
+    // the point at infinity has no affine form and is encoded as zeros
+    if let Some(point) = AffineG1::from_jacobian(point) {
+        point.x().to_big_endian(&mut output[..32]).unwrap();
+        point.y().to_big_endian(&mut output[32..]).unwrap();
+    }
+
+    output
+}
+
+/// 0x06, sum of two G1 points, see EIP-196
+pub fn add(input: &[u8], gas_limit: u64, spec: SpecId) -> PrecompileResult {
+    let cost = add_cost(spec);
+    ensure_gas(cost, gas_limit)?;
+
+    let input = right_pad(input, 128);
+    let p1 = read_g1(&input[..64])?;
+    let p2 = read_g1(&input[64..])?;
+
+    Ok(PrecompileOutput::new(cost, encode_g1(p1 + p2)))
+}
+
+/// 0x07, scalar multiplication of a G1 point, see EIP-196
+pub fn mul(input: &[u8], gas_limit: u64, spec: SpecId) -> PrecompileResult {
+    let cost = mul_cost(spec);
+    ensure_gas(cost, gas_limit)?;
+
+    let input = right_pad(input, 96);
+    let point = read_g1(&input[..64])?;
+    // the scalar is not required to be lower than the group order
+    let scalar = Fr::from_slice(&input[64..]).unwrap();
+
+    Ok(PrecompileOutput::new(cost, encode_g1(point * scalar)))
+}
+
+/// 0x08, 1 if the product of the pairings of each G1 and G2 points is one, see EIP-197
+pub fn pairing(input: &[u8], gas_limit: u64, spec: SpecId) -> PrecompileResult {
+    if !input.len().is_multiple_of(PAIRING_ELEMENT_LEN) {
+        return Err(PrecompileError::InvalidInput(
+            "pairing input must be a multiple of 192 bytes",
+        ));
+    }
+
+    let cost = pairing_cost(spec, input.len() / PAIRING_ELEMENT_LEN);
+    ensure_gas(cost, gas_limit)?;
+
+    let pairs = input
+        .chunks(PAIRING_ELEMENT_LEN)
+        .map(|chunk| Ok((read_g1(&chunk[..64])?, read_g2(&chunk[64..])?)))
+        .collect::<Result<Vec<_>, PrecompileError>>()?;
+
+    let mut output = vec![0u8; 32];
+    output[31] = (pairing_batch(&pairs) == Gt::one()) as u8;
+
+    Ok(PrecompileOutput::new(cost, output))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::decode;
+    use super::*;
+
+    const G1_GENERATOR: &str = concat!(
+        "0000000000000000000000000000000000000000000000000000000000000001",
+        "0000000000000000000000000000000000000000000000000000000000000002"
+    );
+    const G1_GENERATOR_NEG: &str = concat!(
+        "0000000000000000000000000000000000000000000000000000000000000001",
+        "30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd45"
+    );
+    const G1_GENERATOR_DOUBLE: &str = concat!(
+        "030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3",
+        "15ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4"
+    );
+    const G2_GENERATOR: &str = concat!(
+        "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2",
+        "1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed",
+        "090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b",
+        "12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa"
+    );
+
+    #[test]
+    fn bn254_add() {
+        let input = decode(&[G1_GENERATOR, G1_GENERATOR].concat());
+        assert_eq!(
+            add(&input, 150, SpecId::Cancun),
+            Ok(PrecompileOutput::new(150, decode(G1_GENERATOR_DOUBLE)))
+        );
+
+        let input = decode(&[G1_GENERATOR, G1_GENERATOR_NEG].concat());
+        assert_eq!(
+            add(&input, 150, SpecId::Cancun).unwrap().data,
+            vec![0u8; 64]
+        );
+
+        // missing input is the point at infinity
+        assert_eq!(add(&[], 150, SpecId::Cancun).unwrap().data, vec![0u8; 64]);
+        assert_eq!(
+            add(&[], 149, SpecId::Cancun),
+            Err(PrecompileError::OutOfGas)
+        );
+
+        // (1, 1) is not on the curve
+        let mut input = decode(G1_GENERATOR);
+        input[63] = 1;
+        assert!(matches!(
+            add(&input, 150, SpecId::Cancun),
+            Err(PrecompileError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn bn254_mul() {
+        let input = decode(&[G1_GENERATOR, &format!("{:064x}", 2)].concat());
+        assert_eq!(
+            mul(&input, 6000, SpecId::Cancun),
+            Ok(PrecompileOutput::new(6000, decode(G1_GENERATOR_DOUBLE)))
+        );
+
+        // the group order times the generator
+        let input = decode(concat!(
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+            "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001"
+        ));
+        assert_eq!(
+            mul(&input, 6000, SpecId::Cancun).unwrap().data,
+            vec![0u8; 64]
+        );
+    }
+
+    #[test]
+    fn bn254_pairing() {
+        // e(G1, G2) * e(-G1, G2) == 1
+        let input = decode(&[G1_GENERATOR, G2_GENERATOR, G1_GENERATOR_NEG, G2_GENERATOR].concat());
+        let output = pairing(&input, 113_000, SpecId::Cancun).unwrap();
+
+        assert_eq!(output.gas_used, 113_000);
+        assert_eq!(output.data, decode(&format!("{:064x}", 1)));
+
+        // e(G1, G2) alone isn't
+        let output = pairing(&input[..192], 79_000, SpecId::Cancun).unwrap();
+        assert_eq!(output.data, vec![0u8; 32]);
+
+        // no pairs at all is one
+        assert_eq!(
+            pairing(&[], 45_000, SpecId::Cancun),
+            Ok(PrecompileOutput::new(
+                45_000,
+                decode(&format!("{:064x}", 1))
+            ))
+        );
+
+        assert_eq!(
+            pairing(&input, 112_999, SpecId::Cancun),
+            Err(PrecompileError::OutOfGas)
+        );
+        assert!(matches!(
+            pairing(&input[..191], 113_000, SpecId::Cancun),
+            Err(PrecompileError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn bn254_costs_depend_on_hardfork() {
+        for spec in [SpecId::Byzantium, SpecId::Petersburg] {
+            assert_eq!(add_cost(spec), 500);
+            assert_eq!(mul_cost(spec), 40_000);
+            assert_eq!(pairing_cost(spec, 2), 100_000 + 2 * 80_000);
+        }
+
+        assert_eq!(add_cost(SpecId::Istanbul), 150);
+        assert_eq!(mul_cost(SpecId::Istanbul), 6000);
+        assert_eq!(pairing_cost(SpecId::Istanbul, 2), 45_000 + 2 * 34_000);
+
+        // the old price is charged in full
+        assert_eq!(
+            add(&[], 150, SpecId::Petersburg),
+            Err(PrecompileError::OutOfGas)
+        );
+        assert_eq!(add(&[], 500, SpecId::Petersburg).unwrap().gas_used, 500);
+    }
+}
@@ -0,0 +1,112 @@
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+
+use super::{ensure_gas, right_pad, PrecompileOutput, PrecompileResult};
+use crate::spec::SpecId;
+use crate::utils::crypto::keccak256;
+
+pub const ECRECOVER: u64 = 3000;
+
+pub fn cost() -> u64 {
+    ECRECOVER
+}
+
+/// 0x01, address of the key that signed a message hash, as `hash, v, r, s`.
+/// An invalid signature is not an error, nothing is returned instead.
+pub fn run(input: &[u8], gas_limit: u64, _spec: SpecId) -> PrecompileResult {
+    let cost = cost();
+    ensure_gas(cost, gas_limit)?;
+
+    let input = right_pad(input, 128);
+    let data = recover(&input[..32], &input[32..64], &input[64..128])
+        .map(|address| {
+            let mut output = vec![0u8; 32];
+            output[12..].copy_from_slice(&address);
+            output
+        })
+        .unwrap_or_default();
+
+    Ok(PrecompileOutput::new(cost, data))
+}
+
+fn recover(hash: &[u8], v: &[u8], signature: &[u8]) -> Option<[u8; 20]> {
+    // v is a whole word that must be 27 or 28
+    if v[..31].iter().any(|byte| *byte != 0) || !matches!(v[31], 27 | 28) {
+        return None;
+    }
+
+    let mut signature = Signature::from_slice(signature).ok()?;
+    let mut recovery_id = RecoveryId::from_byte(v[31] - 27)?;
+
+    // unlike transactions, signatures with a high s are accepted
+    if let Some(normalized) = signature.normalize_s() {
+        signature = normalized;
+        recovery_id = RecoveryId::from_byte(recovery_id.to_byte() ^ 1)?;
+    }
+
+    let key = VerifyingKey::recover_from_prehash(hash, &signature, recovery_id).ok()?;
+    let point = key.to_encoded_point(false);
+
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&keccak256(&point.as_bytes()[1..])[12..]);
+    Some(address)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{decode, PrecompileError};
+    use super::*;
+
+    const INPUT: &str = "38d18acb67d25c8bb9942764b62f18e17054f66a817bd4295423adf9ed98873e000000000000000000000000000000000000000000000000000000000000001b38d18acb67d25c8bb9942764b62f18e17054f66a817bd4295423adf9ed98873e789d1dd423d25f0772d2748d60f7e4b81bb14d086eba8e8e8efb6dcff8a4ae02";
+
+    #[test]
+    fn recovers_signer_address() {
+        let output = run(&decode(INPUT), 3000, SpecId::Cancun).unwrap();
+
+        assert_eq!(output.gas_used, 3000);
+        assert_eq!(
+            output.data,
+            decode("000000000000000000000000ceaccac640adf55b2028469bd36ba501f28b699d")
+        );
+    }
+
+    #[test]
+    fn invalid_signature_returns_nothing() {
+        // v of 29
+        let mut input = decode(INPUT);
+        input[63] = 29;
+        assert_eq!(
+            run(&input, 3000, SpecId::Cancun).unwrap().data,
+            Vec::<u8>::new()
+        );
+
+        // v with garbage in the high bytes
+        let mut input = decode(INPUT);
+        input[32] = 1;
+        assert_eq!(
+            run(&input, 3000, SpecId::Cancun).unwrap().data,
+            Vec::<u8>::new()
+        );
+
+        // r of zero
+        let mut input = decode(INPUT);
+        input[64..96].fill(0);
+        assert_eq!(
+            run(&input, 3000, SpecId::Cancun).unwrap().data,
+            Vec::<u8>::new()
+        );
+
+        // missing input is zero
+        assert_eq!(
+            run(&[], 3000, SpecId::Cancun).unwrap().data,
+            Vec::<u8>::new()
+        );
+    }
+
+    #[test]
+    fn ecrecover_out_of_gas() {
+        assert_eq!(
+            run(&decode(INPUT), 2999, SpecId::Cancun),
+            Err(PrecompileError::OutOfGas)
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use ripemd::Ripemd160;
+use sha2::{Digest, Sha256};
+
+use super::{ensure_gas, PrecompileOutput, PrecompileResult};
+use crate::gas::num_words;
+use crate::spec::SpecId;
+
+pub const SHA256_BASE: u64 = 60;
+pub const SHA256_WORD: u64 = 12;
+pub const RIPEMD160_BASE: u64 = 600;
+pub const RIPEMD160_WORD: u64 = 120;
+
+pub fn sha256_cost(len: usize) -> u64 {
+    SHA256_BASE + SHA256_WORD * num_words(len as u64)
+}
+
+pub fn ripemd160_cost(len: usize) -> u64 {
+    RIPEMD160_BASE + RIPEMD160_WORD * num_words(len as u64)
+}
+
+/// 0x02, SHA2-256 of the input
+pub fn sha256(input: &[u8], gas_limit: u64, _spec: SpecId) -> PrecompileResult {
+    let cost = sha256_cost(input.len());
+    ensure_gas(cost, gas_limit)?;
+
+    Ok(PrecompileOutput::new(cost, Sha256::digest(input).to_vec()))
+}
+
+/// 0x03, RIPEMD-160 of the input, left padded to 32 bytes
+pub fn ripemd160(input: &[u8], gas_limit: u64, _spec: SpecId) -> PrecompileResult {
+    let cost = ripemd160_cost(input.len());
+    ensure_gas(cost, gas_limit)?;
+
+    let mut output = vec![0u8; 32];
+    output[12..].copy_from_slice(&Ripemd160::digest(input));
+
+    Ok(PrecompileOutput::new(cost, output))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{decode, PrecompileError};
+    use super::*;
+
+    #[test]
+    fn sha256_vectors() {
+        let output = sha256(b"", 100, SpecId::Cancun).unwrap();
+        assert_eq!(output.gas_used, 60);
+        assert_eq!(
+            output.data,
+            decode("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+        );
+
+        let output = sha256(b"abc", 100, SpecId::Cancun).unwrap();
+        assert_eq!(output.gas_used, 72);
+        assert_eq!(
+            output.data,
+            decode("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+
+        assert_eq!(
+            sha256(b"abc", 71, SpecId::Cancun),
+            Err(PrecompileError::OutOfGas)
+        );
+    }
+
+    #[test]
+    fn ripemd160_vectors() {
+        let output = ripemd160(b"", 1000, SpecId::Cancun).unwrap();
+        assert_eq!(output.gas_used, 600);
+        assert_eq!(
+            output.data,
+            decode("0000000000000000000000009c1185a5c5e9fc54612808977ee8f548b2258d31")
+        );
+
+        let output = ripemd160(b"abc", 1000, SpecId::Cancun).unwrap();
+        assert_eq!(output.gas_used, 720);
+        assert_eq!(
+            output.data,
+            decode("0000000000000000000000008eb208f7e05d987a9b044a8e98c6b087f15a0bfc")
+        );
+
+        assert_eq!(
+            ripemd160(&[0; 33], 839, SpecId::Cancun),
+            Err(PrecompileError::OutOfGas)
+        );
+    }
+}
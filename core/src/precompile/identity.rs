@@ -0,0 +1,40 @@
+use super::{ensure_gas, PrecompileOutput, PrecompileResult};
+use crate::gas::num_words;
+use crate::spec::SpecId;
+
+pub const IDENTITY_BASE: u64 = 15;
+pub const IDENTITY_WORD: u64 = 3;
+
+pub fn cost(len: usize) -> u64 {
+    IDENTITY_BASE + IDENTITY_WORD * num_words(len as u64)
+}
+
+/// 0x04, returns the input as is
+pub fn run(input: &[u8], gas_limit: u64, _spec: SpecId) -> PrecompileResult {
+    let cost = cost(input.len());
+    ensure_gas(cost, gas_limit)?;
+
+    Ok(PrecompileOutput::new(cost, input.to_vec()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::PrecompileError;
+    use super::*;
+
+    #[test]
+    fn identity_returns_input() {
+        assert_eq!(
+            run(&[], 15, SpecId::Cancun),
+            Ok(PrecompileOutput::new(15, vec![]))
+        );
+        assert_eq!(
+            run(&[0x20; 33], 100, SpecId::Cancun),
+            Ok(PrecompileOutput::new(21, vec![0x20; 33]))
+        );
+        assert_eq!(
+            run(&[0x20; 33], 20, SpecId::Cancun),
+            Err(PrecompileError::OutOfGas)
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use c_kzg::{ethereum_kzg_settings, Bytes32, Bytes48};
+use sha2::{Digest, Sha256};
+
+use super::{ensure_gas, PrecompileError, PrecompileOutput, PrecompileResult};
+use crate::spec::SpecId;
+
+pub const POINT_EVALUATION: u64 = 50000;
+
+/// Version byte of the hash of a KZG commitment
+pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+/// Field elements in a blob followed by the BLS modulus, returned on success
+const RETURN_VALUE: [u8; 64] = {
+    let mut value = [0u8; 64];
+    value[30] = 0x10;
+
+    let modulus = [
+        0x73, 0xed, 0xa7, 0x53, 0x29, 0x9d, 0x7d, 0x48, 0x33, 0x39, 0xd8, 0x08, 0x09, 0xa1, 0xd8,
+        0x05, 0x53, 0xbd, 0xa4, 0x02, 0xff, 0xfe, 0x5b, 0xfe, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00,
+        0x00, 0x01,
+    ];
+    let mut i = 0;
+    while i < 32 {
+        value[32 + i] = modulus[i];
+        i += 1;
+    }
+
+    value
+};
+
+pub fn cost() -> u64 {
+    POINT_EVALUATION
+}
+
+/// The versioned hash of a commitment, its SHA-256 with the first byte replaced by the version
+pub fn kzg_to_versioned_hash(commitment: &[u8]) -> [u8; 32] {
+    let mut hash: [u8; 32] = Sha256::digest(commitment).into();
+    hash[0] = VERSIONED_HASH_VERSION_KZG;
+    hash
+}
+
+/// 0x0a, verifies that the blob committed to evaluates to `y` at `z`, see EIP-4844.
+/// The input is `versioned_hash, z, y, commitment, proof`.
+pub fn point_evaluation(input: &[u8], gas_limit: u64, _spec: SpecId) -> PrecompileResult {
+    let cost = cost();
+    ensure_gas(cost, gas_limit)?;
+
+    if input.len() != 192 {
+        return Err(PrecompileError::InvalidInput(
+            "point evaluation input must be 192 bytes",
+        ));
+    }
+
+    let commitment = &input[96..144];
+    if input[..32] != kzg_to_versioned_hash(commitment) {
+        return Err(PrecompileError::InvalidInput(
+            "versioned hash does not match commitment",
+        ));
+    }
+
+    let invalid = |_| PrecompileError::InvalidInput("invalid point evaluation input");
+    let verified = ethereum_kzg_settings(0)
+        .verify_kzg_proof(
+            &Bytes48::from_bytes(commitment).map_err(invalid)?,
+            &Bytes32::from_bytes(&input[32..64]).map_err(invalid)?,
+            &Bytes32::from_bytes(&input[64..96]).map_err(invalid)?,
+            &Bytes48::from_bytes(&input[144..]).map_err(invalid)?,
+        )
+        .map_err(invalid)?;
+
+    if !verified {
+        return Err(PrecompileError::InvalidInput(
+            "point evaluation proof failed",
+        ));
+    }
+
+    Ok(PrecompileOutput::new(cost, RETURN_VALUE.to_vec()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::decode;
+    use super::*;
+
+    const INPUT: &str = concat!(
+        "01e798154708fe7789429634053cbf9f99b619f9f084048927333fce637f549b",
+        "564c0a11a0f704f4fc3e8acfe0f8245f0ad1347b378fbf96e206da11a5d36306",
+        "24d25032e67a7e6a4910df5834b8fe70e6bcfeeac0352434196bdf4b2485d5a1",
+        "8f59a8d2a1a625a17f3fea0fe5eb8c896db3764f3185481bc22f91b4aaffcca25f26936857bc3a7c2539ea8ec3a952b7",
+        "873033e038326e87ed3e1276fd140253fa08e9fc25fb2d9a98527fc22a2c9612fbeafdad446cbc7bcdbdcd780af2c16a"
+    );
+
+    #[test]
+    fn point_evaluation_verifies_proof() {
+        let output = point_evaluation(&decode(INPUT), 50000, SpecId::Cancun).unwrap();
+
+        assert_eq!(output.gas_used, 50000);
+        assert_eq!(
+            output.data,
+            decode(concat!(
+                "0000000000000000000000000000000000000000000000000000000000001000",
+                "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001"
+            ))
+        );
+
+        assert_eq!(
+            point_evaluation(&decode(INPUT), 49999, SpecId::Cancun),
+            Err(PrecompileError::OutOfGas)
+        );
+    }
+
+    #[test]
+    fn point_evaluation_rejects_invalid_input() {
+        let input = decode(INPUT);
+        assert!(point_evaluation(&input[..191], 50000, SpecId::Cancun).is_err());
+
+        // the versioned hash doesn't match the commitment
+        let mut input = decode(INPUT);
+        input[31] ^= 1;
+        assert!(point_evaluation(&input, 50000, SpecId::Cancun).is_err());
+
+        // a different evaluation
+        let mut input = decode(INPUT);
+        input[95] ^= 1;
+        assert!(point_evaluation(&input, 50000, SpecId::Cancun).is_err());
+    }
+}
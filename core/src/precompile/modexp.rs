@@ -0,0 +1,264 @@
+use ethereum_types::U256;
+use num_bigint::BigUint;
+
+use super::{right_pad, PrecompileError, PrecompileOutput, PrecompileResult};
+use crate::spec::SpecId;
+
+pub const MODEXP_MIN: u64 = 200;
+
+/// Longest base and modulus, they cost about 6 trillion gas so only a gas limit
+/// given by hand reaches it, reaching it is running out of gas
+pub const MAX_LENGTH: u64 = 32 * 1024 * 1024;
+
+/// Cost of MODEXP following EIP-2565 since Berlin and EIP-198 before, `exp_head`
+/// being the first 32 bytes of the exponent
+pub fn cost(spec: SpecId, base_len: U256, exp_len: U256, mod_len: U256, exp_head: U256) -> U256 {
+    let head_bits = U256::from(exp_head.bits().saturating_sub(1));
+    let iteration_count = if exp_len <= U256::from(32) {
+        head_bits
+    } else {
+        (exp_len - 32).saturating_mul(U256::from(8)) + head_bits
+    }
+    .max(U256::one());
+
+    let max_len = base_len.max(mod_len);
+
+    if !spec.is_enabled(SpecId::Berlin) {
+        return eip198_complexity(max_len).saturating_mul(iteration_count) / 20;
+    }
+
+    let words = max_len.saturating_add(U256::from(7)) / 8;
+    let multiplication_complexity = words.saturating_mul(words);
+
+    let cost = multiplication_complexity.saturating_mul(iteration_count) / 3;
+    cost.max(U256::from(MODEXP_MIN))
+}
+
+/// The multiplication complexity of EIP-198, `len` being in bytes
+fn eip198_complexity(len: U256) -> U256 {
+    let square = len.saturating_mul(len);
+
+    if len <= U256::from(64) {
+        square
+    } else if len <= U256::from(1024) {
+        square / 4 + len * 96 - 3072
+    } else {
+        (square / 16)
+            .saturating_add(len.saturating_mul(U256::from(480)))
+            .saturating_sub(U256::from(199_680))
+    }
+}
+
+/// `len` bytes of `data` starting at `offset`, with zeros past its end
+fn read_padded(data: &[u8], offset: U256, len: usize) -> Vec<u8> {
+    if offset >= U256::from(data.len()) {
+        return vec![0u8; len];
+    }
+
+    right_pad(&data[offset.as_usize()..], len)
+}
+
+/// 0x05, `base ** exp % mod` with the lengths of the three given first, see EIP-198
+pub fn run(input: &[u8], gas_limit: u64, spec: SpecId) -> PrecompileResult {
+    let header = right_pad(input, 96);
+    let base_len = U256::from_big_endian(&header[..32]);
+    let exp_len = U256::from_big_endian(&header[32..64]);
+    let mod_len = U256::from_big_endian(&header[64..96]);
+    let data = input.get(96..).unwrap_or_default();
+
+    let exp_head_len = exp_len.min(U256::from(32)).as_usize();
+    let exp_head = U256::from_big_endian(&read_padded(data, base_len, exp_head_len));
+
+    let cost = cost(spec, base_len, exp_len, mod_len, exp_head);
+    if cost > U256::from(gas_limit) {
+        return Err(PrecompileError::OutOfGas);
+    }
+    let cost = cost.as_u64();
+
+    // nothing to compute, the exponent may be too big to even be read
+    if mod_len.is_zero() {
+        return Ok(PrecompileOutput::new(cost, Vec::new()));
+    }
+
+    if base_len > U256::from(MAX_LENGTH) || mod_len > U256::from(MAX_LENGTH) {
+        return Err(PrecompileError::OutOfGas);
+    }
+
+    // bounded above, except the exponent which can't be longer than the input
+    let (base_len, mod_len) = (base_len.as_usize(), mod_len.as_usize());
+    let exp_len = exp_len.min(U256::from(data.len())).as_usize();
+
+    let base = BigUint::from_bytes_be(&read_padded(data, U256::zero(), base_len));
+    let exponent = BigUint::from_bytes_be(&read_padded(data, U256::from(base_len), exp_len));
+    let modulus =
+        BigUint::from_bytes_be(&read_padded(data, U256::from(base_len) + exp_len, mod_len));
+
+    let mut output = vec![0u8; mod_len];
+
+    if modulus != BigUint::ZERO {
+        let result = base.modpow(&exponent, &modulus).to_bytes_be();
+        output[mod_len - result.len()..].copy_from_slice(&result);
+    }
+
+    Ok(PrecompileOutput::new(cost, output))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::decode;
+    use super::*;
+
+    #[test]
+    fn modexp_eip198_examples() {
+        // Fermat's little theorem, 3 ** (p - 1) % p
+        let input = decode(concat!(
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000000000000000000000000000020",
+            "0000000000000000000000000000000000000000000000000000000000000020",
+            "03",
+            "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2e",
+            "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f"
+        ));
+        let output = run(&input, 100_000, SpecId::Cancun).unwrap();
+
+        assert_eq!(output.gas_used, 1360);
+        assert_eq!(
+            output.data,
+            decode("0000000000000000000000000000000000000000000000000000000000000001")
+        );
+
+        // a zero base with the base length of 0, and the modulus past the input
+        let input = decode(concat!(
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000020",
+            "0000000000000000000000000000000000000000000000000000000000000020",
+            "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2e"
+        ));
+        let output = run(&input, 100_000, SpecId::Cancun).unwrap();
+
+        assert_eq!(output.gas_used, 1360);
+        assert_eq!(output.data, vec![0u8; 32]);
+    }
+
+    #[test]
+    fn modexp_small_values() {
+        // 2 ** 10 % 1000 with two bytes for the modulus
+        let input = decode(concat!(
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+            "02",
+            "0a",
+            "03e8"
+        ));
+        let output = run(&input, 200, SpecId::Cancun).unwrap();
+
+        assert_eq!(output.gas_used, 200);
+        assert_eq!(output.data, vec![0x00, 0x18]);
+    }
+
+    #[test]
+    fn modexp_huge_lengths() {
+        // an exponent of 2^255 bytes costs nothing without a base or modulus
+        let mut input = vec![0u8; 96];
+        input[32] = 0x80;
+
+        assert_eq!(
+            run(&input, 200, SpecId::Cancun),
+            Ok(PrecompileOutput::new(200, vec![]))
+        );
+
+        // but can't be paid for otherwise
+        input[95] = 1;
+        assert_eq!(
+            run(&input, 30_000_000, SpecId::Cancun),
+            Err(PrecompileError::OutOfGas)
+        );
+
+        // neither can a huge modulus
+        let mut input = vec![0u8; 96];
+        input[64] = 0x01;
+        assert_eq!(
+            run(&input, 30_000_000, SpecId::Cancun),
+            Err(PrecompileError::OutOfGas)
+        );
+    }
+
+    #[test]
+    fn modexp_lengths_are_capped() {
+        let input = |mod_len: u64| {
+            let mut input = vec![0u8; 96];
+            U256::from(mod_len).to_big_endian(&mut input[64..]);
+            input
+        };
+
+        // a zero modulus of the longest length
+        let output = run(&input(MAX_LENGTH), u64::MAX, SpecId::Cancun).unwrap();
+        assert_eq!(output.data.len(), MAX_LENGTH as usize);
+
+        // 32 GiB can be paid for with such a gas limit, but is never allocated
+        assert_eq!(
+            run(&input(1 << 35), u64::MAX, SpecId::Cancun),
+            Err(PrecompileError::OutOfGas)
+        );
+    }
+
+    #[test]
+    fn modexp_cost_eip2565() {
+        let cost = |base_len: u64, exp_len: u64, mod_len: u64, exp_head: U256| {
+            super::cost(
+                SpecId::Berlin,
+                U256::from(base_len),
+                U256::from(exp_len),
+                U256::from(mod_len),
+                exp_head,
+            )
+        };
+
+        assert_eq!(cost(1, 1, 1, U256::zero()), U256::from(200));
+        assert_eq!(cost(64, 32, 64, U256::MAX), U256::from(64 * 255 / 3));
+        // 8 bits per byte past the first 32, plus the highest bit of the head
+        assert_eq!(
+            cost(256, 64, 256, U256::one()),
+            U256::from(32 * 32 * 256 / 3)
+        );
+    }
+
+    #[test]
+    fn modexp_cost_eip198_before_berlin() {
+        let cost = |spec, base_len: u64, exp_len: u64, mod_len: u64, exp_head: U256| {
+            super::cost(
+                spec,
+                U256::from(base_len),
+                U256::from(exp_len),
+                U256::from(mod_len),
+                exp_head,
+            )
+        };
+        let fermat_exponent = U256::MAX - 1;
+
+        // the first example of EIP-198
+        assert_eq!(
+            cost(SpecId::Istanbul, 1, 32, 32, fermat_exponent),
+            U256::from(13056)
+        );
+        assert_eq!(
+            cost(SpecId::Berlin, 1, 32, 32, fermat_exponent),
+            U256::from(1360)
+        );
+
+        // no minimum
+        assert_eq!(cost(SpecId::Byzantium, 1, 1, 1, U256::zero()), U256::zero());
+        assert_eq!(cost(SpecId::Berlin, 1, 1, 1, U256::zero()), U256::from(200));
+
+        // both larger complexity brackets
+        assert_eq!(
+            cost(SpecId::Byzantium, 100, 1, 0, U256::from(2)),
+            U256::from((100 * 100 / 4 + 96 * 100 - 3072) / 20)
+        );
+        assert_eq!(
+            cost(SpecId::Byzantium, 2000, 1, 0, U256::from(2)),
+            U256::from((2000 * 2000 / 16 + 480 * 2000 - 199_680) / 20)
+        );
+    }
+}
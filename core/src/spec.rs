@@ -0,0 +1,135 @@
+use std::{fmt, str::FromStr};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum SpecError {
+    #[error("unknown hardfork : {0}")]
+    UnknownHardfork(String),
+}
+
+/// Ethereum hardforks, in activation order, up to the last one whose rules are implemented
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(try_from = "String")]
+pub enum SpecId {
+    Frontier,
+    Homestead,
+    TangerineWhistle,
+    SpuriousDragon,
+    Byzantium,
+    Constantinople,
+    Petersburg,
+    Istanbul,
+    Berlin,
+    London,
+    Merge,
+    Shanghai,
+    #[default]
+    Cancun,
+}
+
+impl SpecId {
+    pub const ALL: [SpecId; 13] = [
+        SpecId::Frontier,
+        SpecId::Homestead,
+        SpecId::TangerineWhistle,
+        SpecId::SpuriousDragon,
+        SpecId::Byzantium,
+        SpecId::Constantinople,
+        SpecId::Petersburg,
+        SpecId::Istanbul,
+        SpecId::Berlin,
+        SpecId::London,
+        SpecId::Merge,
+        SpecId::Shanghai,
+        SpecId::Cancun,
+    ];
+
+    /// Whether the rules introduced by `fork` apply
+    pub fn is_enabled(self, fork: SpecId) -> bool {
+        self >= fork
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SpecId::Frontier => "frontier",
+            SpecId::Homestead => "homestead",
+            SpecId::TangerineWhistle => "tangerine-whistle",
+            SpecId::SpuriousDragon => "spurious-dragon",
+            SpecId::Byzantium => "byzantium",
+            SpecId::Constantinople => "constantinople",
+            SpecId::Petersburg => "petersburg",
+            SpecId::Istanbul => "istanbul",
+            SpecId::Berlin => "berlin",
+            SpecId::London => "london",
+            SpecId::Merge => "merge",
+            SpecId::Shanghai => "shanghai",
+            SpecId::Cancun => "cancun",
+        }
+    }
+}
+
+impl fmt::Display for SpecId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Case insensitive, ignoring dashes and underscores. The Merge is also known as Paris.
+impl FromStr for SpecId {
+    type Err = SpecError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.to_lowercase().replace(['-', '_', ' '], "");
+
+        if normalized == "paris" {
+            return Ok(SpecId::Merge);
+        }
+
+        SpecId::ALL
+            .into_iter()
+            .find(|spec| spec.name().replace('-', "") == normalized)
+            .ok_or_else(|| SpecError::UnknownHardfork(s.to_string()))
+    }
+}
+
+impl TryFrom<String> for SpecId {
+    type Error = SpecError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn later_forks_keep_earlier_rules() {
+        assert!(SpecId::Cancun.is_enabled(SpecId::London));
+        assert!(SpecId::Cancun.is_enabled(SpecId::Cancun));
+        assert!(!SpecId::Shanghai.is_enabled(SpecId::Cancun));
+    }
+
+    #[test]
+    fn parse_hardfork_names() {
+        for spec in SpecId::ALL {
+            assert_eq!(spec.to_string().parse::<SpecId>().unwrap(), spec);
+        }
+
+        assert_eq!("Cancun".parse::<SpecId>().unwrap(), SpecId::Cancun);
+        assert_eq!(
+            "TangerineWhistle".parse::<SpecId>().unwrap(),
+            SpecId::TangerineWhistle
+        );
+        assert_eq!(
+            "spurious_dragon".parse::<SpecId>().unwrap(),
+            SpecId::SpuriousDragon
+        );
+        assert_eq!("paris".parse::<SpecId>().unwrap(), SpecId::Merge);
+        assert!("dao".parse::<SpecId>().is_err());
+        assert!("prague".parse::<SpecId>().is_err());
+    }
+}
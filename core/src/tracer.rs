@@ -0,0 +1,263 @@
+//! Traces in the EIP-3155 format, one JSON object per operation followed by a summary
+
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+use crate::environment::ExecutionResult;
+use crate::executor::ExecutionContext;
+use crate::halt::HaltReason;
+use crate::inspector::{FrameEnd, Inspector};
+use crate::operation::OpCode;
+use crate::spec::SpecId;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Step {
+    pc: usize,
+    op: u8,
+    gas: String,
+    gas_cost: String,
+    mem_size: usize,
+    stack: Vec<String>,
+    depth: usize,
+    refund: i64,
+    op_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// The last line, there is no `stateRoot` as the state is not merkleized
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Summary {
+    output: String,
+    gas_used: String,
+    pass: bool,
+    fork: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// An operation that started but did not end yet
+struct PendingStep {
+    step: Step,
+    gas: u64,
+    /// gas given back by the frame it entered, for calls and creates
+    returned: u64,
+    /// lines of the frames it entered, they are written after its own
+    nested: Vec<String>,
+}
+
+/// Writes every operation as a line of JSON. The gas cost of a call is only known
+/// once it returned, so the lines of nested frames are held back until then.
+pub struct JsonTracer<W: Write> {
+    output: W,
+    pending: Vec<PendingStep>,
+    /// the first write that failed, the trace stops there
+    error: Option<io::Error>,
+}
+
+impl<W: Write> JsonTracer<W> {
+    pub fn new(output: W) -> Self {
+        Self {
+            output,
+            pending: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Writes the summary line, or the error that interrupted the trace
+    pub fn summary(&mut self, result: &ExecutionResult, fork: SpecId) -> io::Result<()> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+
+        let summary = Summary {
+            output: to_hex(&result.data),
+            gas_used: format!("{:#x}", result.gas_used),
+            pass: result.is_success(),
+            fork: fork.name().to_string(),
+            error: result.halt_reason().map(|reason| reason.to_string()),
+        };
+
+        writeln!(self.output, "{}", serde_json::to_string(&summary)?)?;
+        self.output.flush()
+    }
+
+    fn write_line(&mut self, line: String) {
+        if self.error.is_none() {
+            self.error = writeln!(self.output, "{line}").err();
+        }
+    }
+}
+
+impl<W: Write> Inspector for JsonTracer<W> {
+    fn step(&mut self, ctx: &ExecutionContext, opcode: OpCode) {
+        let machine = &ctx.execution_machine;
+        let gas = ctx.gas.remaining();
+
+        self.pending.push(PendingStep {
+            step: Step {
+                pc: machine.pc.get(),
+                op: opcode.byte(),
+                gas: format!("{gas:#x}"),
+                gas_cost: String::new(),
+                mem_size: machine.memory.used_capacity(),
+                stack: machine
+                    .stack
+                    .items()
+                    .iter()
+                    .map(|item| format!("{item:#x}"))
+                    .collect(),
+                depth: ctx.depth + 1,
+                refund: ctx.gas.refunded(),
+                op_name: opcode.to_string(),
+                error: None,
+            },
+            gas,
+            returned: 0,
+            nested: Vec::new(),
+        });
+    }
+
+    fn step_end(&mut self, ctx: &ExecutionContext, _opcode: OpCode, halt: Option<HaltReason>) {
+        let Some(PendingStep {
+            mut step,
+            gas,
+            returned,
+            nested,
+        }) = self.pending.pop()
+        else {
+            return;
+        };
+
+        let cost = (gas + returned).saturating_sub(ctx.gas.remaining());
+        step.gas_cost = format!("{cost:#x}");
+        step.error = halt.map(|reason| reason.to_string());
+
+        // plain structs always serialize
+        let Ok(line) = serde_json::to_string(&step) else {
+            return;
+        };
+
+        match self.pending.last_mut() {
+            Some(parent) => {
+                parent.nested.push(line);
+                parent.nested.extend(nested);
+            }
+            None => {
+                self.write_line(line);
+                for line in nested {
+                    self.write_line(line);
+                }
+            }
+        }
+    }
+
+    fn call_end(&mut self, end: &FrameEnd) {
+        if let Some(pending) = self.pending.last_mut() {
+            pending.returned = end.gas_remaining;
+        }
+    }
+
+    fn create_end(&mut self, end: &FrameEnd) {
+        if let Some(pending) = self.pending.last_mut() {
+            pending.returned = end.gas_remaining;
+        }
+    }
+}
+
+fn to_hex(data: &[u8]) -> String {
+    let digits: String = data.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!("0x{digits}")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use ethereum_types::{H160, U256};
+    use serde_json::Value;
+
+    use super::*;
+    use crate::environment::{AccountState, GlobalEnvironment, GlobalStorage};
+    use crate::executor::ExecutionEnvironment;
+
+    fn trace(accounts: GlobalStorage, code: Vec<u8>) -> Vec<Value> {
+        let tracer = Rc::new(RefCell::new(JsonTracer::new(Vec::new())));
+        let global_env = Rc::new(GlobalEnvironment {
+            global_storage: Rc::new(RefCell::new(accounts)),
+            ..Default::default()
+        });
+
+        let result = ExecutionContext::new(ExecutionEnvironment::default(), global_env)
+            .with_inspector(tracer.clone())
+            .run(code);
+        tracer
+            .borrow_mut()
+            .summary(&result, SpecId::Cancun)
+            .unwrap();
+
+        let output = String::from_utf8(tracer.borrow().output.clone()).unwrap();
+        output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn one_line_per_operation_and_a_summary() {
+        // PUSH1 1, PUSH1 2, ADD then the implicit STOP
+        let lines = trace(GlobalStorage::new(), vec![0x60, 0x01, 0x60, 0x02, 0x01]);
+
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[2]["pc"], 4);
+        assert_eq!(lines[2]["op"], 0x01);
+        assert_eq!(lines[2]["opName"], "ADD");
+        assert_eq!(lines[2]["gasCost"], "0x3");
+        assert_eq!(lines[2]["stack"], serde_json::json!(["0x1", "0x2"]));
+        assert_eq!(lines[2]["depth"], 1);
+        assert_eq!(lines[3]["opName"], "STOP");
+
+        assert_eq!(lines[4]["pass"], true);
+        assert_eq!(lines[4]["gasUsed"], "0x9");
+        assert_eq!(lines[4]["output"], "0x");
+    }
+
+    #[test]
+    fn nested_frames_follow_their_call() {
+        let callee = H160::from_low_u64_be(0x1000);
+        let accounts = GlobalStorage::from([(
+            callee,
+            AccountState {
+                // INVALID
+                code: Some(vec![0xfe].into()),
+                balance: U256::zero(),
+                ..Default::default()
+            },
+        )]);
+
+        // STATICCALL(0xff, 0x1000, 0, 0, 0, 0)
+        let code = vec![
+            0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x61, 0x10, 0x00, 0x60, 0xff, 0xfa,
+        ];
+        let lines = trace(accounts, code);
+
+        let names: Vec<_> = lines[6..9]
+            .iter()
+            .map(|line| {
+                (
+                    line["opName"].as_str().unwrap(),
+                    line["depth"].as_u64().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(names, vec![("STATICCALL", 1), ("INVALID", 2), ("STOP", 1)]);
+
+        // the cold access and the 0xff given to the callee, which are lost
+        assert_eq!(lines[6]["gasCost"], format!("{:#x}", 2600 + 0xff));
+        assert_eq!(lines[7]["error"], "designated invalid operation 0xfe");
+        assert_eq!(lines[9]["pass"], true);
+    }
+}
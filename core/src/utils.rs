@@ -1,3 +1,5 @@
+pub mod crypto;
+
 use ethereum_types::{H160, U256};
 
 pub fn convert_u256_to_eth_address(value: U256) -> H160 {
@@ -21,6 +23,19 @@ pub fn compressed_u256_bytes(value: U256) -> Vec<u8> {
     compressed
 }
 
+/// `size` bytes of `data` from `offset`, zero padded past the end
+pub fn copy_padded(data: &[u8], offset: U256, size: usize) -> Vec<u8> {
+    let mut copy = vec![0u8; size];
+
+    if offset < U256::from(data.len()) {
+        let offset = offset.as_usize();
+        let len = size.min(data.len() - offset);
+        copy[..len].copy_from_slice(&data[offset..(offset + len)]);
+    }
+
+    copy
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
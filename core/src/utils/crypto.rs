@@ -0,0 +1,145 @@
+use ethereum_types::{H160, H256};
+use sha3::{Digest, Keccak256};
+
+/// Hash of empty code, `keccak256([])`
+pub const KECCAK_EMPTY: H256 = H256([
+    0xc5, 0xd2, 0x46, 0x01, 0x86, 0xf7, 0x23, 0x3c, 0x92, 0x7e, 0x7d, 0xb2, 0xdc, 0xc7, 0x03, 0xc0,
+    0xe5, 0x00, 0xb6, 0x53, 0xca, 0x82, 0x27, 0x3b, 0x7b, 0xfa, 0xd8, 0x04, 0x5d, 0x85, 0xa4, 0x70,
+]);
+
+/// Root of an empty Merkle Patricia trie, `keccak256(rlp(""))`
+pub const EMPTY_TRIE_ROOT: H256 = H256([
+    0x56, 0xe8, 0x1f, 0x17, 0x1b, 0xcc, 0x55, 0xa6, 0xff, 0x83, 0x45, 0xe6, 0x92, 0xc0, 0xf8, 0x6e,
+    0x5b, 0x48, 0xe0, 0x1b, 0x99, 0x6c, 0xad, 0xc0, 0x01, 0x62, 0x2f, 0xb5, 0xe3, 0x63, 0xb4, 0x21,
+]);
+
+/// Keccak-256, the hash Ethereum calls SHA3 which differs from the NIST SHA3-256
+pub fn keccak256(data: impl AsRef<[u8]>) -> H256 {
+    H256::from_slice(&Keccak256::digest(data.as_ref()))
+}
+
+/// Function selector of a signature like `transfer(address,uint256)`
+pub fn selector(signature: &str) -> [u8; 4] {
+    let mut selector = [0u8; 4];
+    selector.copy_from_slice(&keccak256(signature).as_bytes()[..4]);
+    selector
+}
+
+/// First topic of the logs of an event like `Transfer(address,address,uint256)`
+pub fn event_topic(signature: &str) -> H256 {
+    keccak256(signature)
+}
+
+/// Address of a contract deployed with CREATE, `keccak256(rlp([sender, nonce]))[12..]`
+pub fn create_address(sender: H160, nonce: u64) -> H160 {
+    let nonce_be = nonce.to_be_bytes();
+    let nonce_be = &nonce_be[nonce.leading_zeros() as usize / 8..];
+
+    // rlp of the address is always 0x80 + 20 followed by the address itself
+    let mut payload = vec![0x80 + 20];
+    payload.extend_from_slice(sender.as_bytes());
+
+    match nonce_be {
+        [] => payload.push(0x80),
+        [byte] if *byte < 0x80 => payload.push(*byte),
+        bytes => {
+            payload.push(0x80 + bytes.len() as u8);
+            payload.extend_from_slice(bytes);
+        }
+    }
+
+    let mut encoded = vec![0xc0 + payload.len() as u8];
+    encoded.extend(payload);
+
+    H160::from_slice(&keccak256(encoded)[12..])
+}
+
+/// Address of a contract deployed with CREATE2, see EIP-1014. The init code is
+/// only given by its hash, which the caller already has.
+pub fn create2_address(sender: H160, salt: H256, init_code_hash: H256) -> H160 {
+    let mut hasher = Keccak256::new();
+    hasher.update([0xff]);
+    hasher.update(sender.as_bytes());
+    hasher.update(salt.as_bytes());
+    hasher.update(init_code_hash);
+
+    H160::from_slice(&hasher.finalize()[12..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_keccak256() {
+        assert_eq!(keccak256([]), KECCAK_EMPTY);
+        assert_eq!(keccak256([0x80]), EMPTY_TRIE_ROOT);
+        assert_eq!(
+            keccak256("hello world"),
+            H256::from_str("0x47173285a8d7341e5e972fc677286384f802f8ef42a5ec5f03bbfa254cb01fad")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_selector_and_event_topic() {
+        assert_eq!(
+            selector("transfer(address,uint256)"),
+            [0xa9, 0x05, 0x9c, 0xbb]
+        );
+        assert_eq!(selector("balanceOf(address)"), [0x70, 0xa0, 0x82, 0x31]);
+        assert_eq!(
+            event_topic("Transfer(address,address,uint256)"),
+            H256::from_str("0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_create_address() {
+        let sender = H160::from_str("0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0").unwrap();
+
+        assert_eq!(
+            create_address(sender, 0),
+            H160::from_str("0xcd234a471b72ba2f1ccf0a70fcaba648a5eecd8d").unwrap()
+        );
+        assert_eq!(
+            create_address(sender, 1),
+            H160::from_str("0x343c43a37d37dff08ae8c4a11544c718abb4fcf8").unwrap()
+        );
+        assert_eq!(
+            create_address(sender, 2),
+            H160::from_str("0xf778b86fa74e846c4f0a1fbd1335fe81c00a0c91").unwrap()
+        );
+        assert_eq!(
+            create_address(sender, 3),
+            H160::from_str("0xfffd933a0bc612844eaf0c6fe3e5b8e9b6c1d19c").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_create2_address() {
+        // examples from https://eips.ethereum.org/EIPS/eip-1014#examples
+        assert_eq!(
+            create2_address(H160::zero(), H256::zero(), keccak256([0x00])),
+            H160::from_str("0x4D1A2e2bB4F88F0250f26Ffff098B0b30B26BF38").unwrap()
+        );
+        assert_eq!(
+            create2_address(
+                H160::from_str("0xdeadbeef00000000000000000000000000000000").unwrap(),
+                H256::zero(),
+                keccak256([0x00]),
+            ),
+            H160::from_str("0xB928f69Bb1D91Cd65274e3c79d8986362984fDA3").unwrap()
+        );
+        assert_eq!(
+            create2_address(
+                H160::from_low_u64_be(0xdeadbeef),
+                H256::from_low_u64_be(0xcafebabe),
+                keccak256([0xde, 0xad, 0xbe, 0xef]),
+            ),
+            H160::from_str("0x60f3f640a8508fC6a86d45DF051962668E1e8AC7").unwrap()
+        );
+    }
+}
@@ -1,8 +1,89 @@
 #[cfg(test)]
 mod tests {
 
-    use ethereum_types::U256;
-    use evm_core::executor::ExecutionContext;
+    use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+    use ethereum_types::{H160, H256, U256};
+    use evm_core::{
+        diff::Diff,
+        environment::{
+            AccountState, CurrentBlockInformation, ExecutionStatus, GlobalEnvironment,
+            GlobalStorage,
+        },
+        evm::{Evm, EvmConfig},
+        executor::{ExecutionContext, ExecutionEnvironment, MAX_CALL_DEPTH, MAX_MEMORY_SIZE},
+        halt::HaltReason,
+        inspector::{FrameEnd, Inspector},
+        log::Log,
+        operation::{OpCode, OPCODES},
+        spec::SpecId,
+        utils::crypto::{create2_address, create_address, keccak256, KECCAK_EMPTY},
+    };
+
+    fn decode(code: &str) -> Vec<u8> {
+        (0..code.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&code[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn context_with_accounts(
+        accounts: Vec<(H160, AccountState)>,
+        execution_env: ExecutionEnvironment,
+    ) -> ExecutionContext {
+        context_with_spec(SpecId::default(), accounts, execution_env)
+    }
+
+    fn context_with_spec(
+        spec_id: SpecId,
+        accounts: Vec<(H160, AccountState)>,
+        execution_env: ExecutionEnvironment,
+    ) -> ExecutionContext {
+        let global_storage: GlobalStorage = accounts.into_iter().collect();
+
+        ExecutionContext::new(
+            execution_env,
+            Rc::new(GlobalEnvironment {
+                spec_id,
+                global_storage: Rc::new(RefCell::new(global_storage)),
+                ..Default::default()
+            }),
+        )
+    }
+
+    fn contract(code: &str) -> AccountState {
+        AccountState {
+            code: Some(decode(code).into()),
+            ..Default::default()
+        }
+    }
+
+    fn context_with_storage(
+        address: H160,
+        storage: BTreeMap<U256, U256>,
+        gas_limit: u64,
+    ) -> ExecutionContext {
+        let mut global_storage = GlobalStorage::new();
+        global_storage.insert(
+            address,
+            AccountState {
+                storage,
+                ..Default::default()
+            },
+        );
+
+        ExecutionContext::new(
+            ExecutionEnvironment {
+                contract_address: address,
+                gas_limit,
+                ..Default::default()
+            },
+            Rc::new(GlobalEnvironment {
+                global_storage: Rc::new(RefCell::new(global_storage)),
+                ..Default::default()
+            }),
+        )
+    }
 
     #[test]
     fn simple_arithmetic_operations() {
@@ -11,7 +92,7 @@ mod tests {
         ];
         let mut context = ExecutionContext::default();
 
-        assert!(context.run(program).is_ok());
+        assert!(context.run(program).is_success());
         assert_eq!(context.execution_machine.stack.height(), 1);
         assert_eq!(
             U256::from(0x09),
@@ -24,10 +105,35 @@ mod tests {
         let program = vec![0x60, 0x01, 0x60, 0x20, 0x10, 0x15, 0x80, 0x14, 0x15, 0x15];
         let mut context = ExecutionContext::default();
 
-        assert!(context.run(program).is_ok());
+        assert!(context.run(program).is_success());
         assert_eq!(U256::one(), context.execution_machine.stack.pop().unwrap());
     }
 
+    #[test]
+    fn signed_operations() {
+        let minus = |value: u64| U256::MAX - value + 1;
+        let cases = [
+            // SDIV(-10, 3)
+            ("6003600a60000305", minus(3)),
+            // SMOD(-10, 3)
+            ("6003600a60000307", minus(1)),
+            // SLT(-1, 1) and SGT(-1, 1)
+            ("600160001912", U256::one()),
+            ("600160001913", U256::zero()),
+            // SAR(2, -16)
+            ("601060000360021d", minus(4)),
+            // SIGNEXTEND(0, 0xff)
+            ("60ff60000b", U256::MAX),
+        ];
+
+        for (program, expected) in cases {
+            let mut context = ExecutionContext::default();
+
+            assert!(context.run(decode(program)).is_success());
+            assert_eq!(context.execution_machine.stack.pop().unwrap(), expected);
+        }
+    }
+
     #[test]
     fn bitwise_operations() {
         let program = vec![
@@ -36,7 +142,7 @@ mod tests {
         ];
         let mut context = ExecutionContext::default();
 
-        assert!(context.run(program).is_ok());
+        assert!(context.run(program).is_success());
         assert_eq!(
             U256::from(0b0100),
             context.execution_machine.stack.pop().unwrap()
@@ -50,7 +156,7 @@ mod tests {
         ];
         let mut context = ExecutionContext::default();
 
-        assert!(context.run(program).is_ok());
+        assert!(context.run(program).is_success());
         assert_eq!(
             context.execution_machine.stack.get_from_top(0).unwrap(),
             U256::from(0x69)
@@ -61,12 +167,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn swap_reaches_deep_items() {
+        // PUSH1 1 PUSH1 2 PUSH1 3 SWAP2
+        let mut context = ExecutionContext::default();
+
+        assert!(context.run(decode("60016002600391")).is_success());
+        assert_eq!(context.execution_machine.stack.pop().unwrap(), U256::one());
+        assert_eq!(
+            context.execution_machine.stack.pop().unwrap(),
+            U256::from(2)
+        );
+        assert_eq!(
+            context.execution_machine.stack.pop().unwrap(),
+            U256::from(3)
+        );
+    }
+
     #[test]
     fn dup_operations() {
         let program = vec![0x60, 0x69, 0x60, 0x33, 0x80];
         let mut context = ExecutionContext::default();
 
-        assert!(context.run(program).is_ok());
+        assert!(context.run(program).is_success());
         assert_eq!(context.execution_machine.stack.height(), 3);
         assert_eq!(
             context.execution_machine.stack.get_from_top(0).unwrap(),
@@ -85,7 +208,7 @@ mod tests {
         ];
         let mut context = ExecutionContext::default();
 
-        assert!(context.run(program).is_ok());
+        assert!(context.run(program).is_success());
         assert_eq!(context.execution_machine.stack.height(), 4);
         assert_eq!(
             context.execution_machine.stack.get_from_top(0).unwrap(),
@@ -107,18 +230,48 @@ mod tests {
             0x60, 0x69, 0x80, 0x60, 0x42, 0x60, 0x00, 0x80, 0xA3, 0x60, 0x77, 0x60, 0x00, 0x80,
             0xA1,
         ];
-        let mut context = ExecutionContext::default();
+        let logs = ExecutionContext::default().run(program).logs;
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].topics.len(), 3);
+        assert_eq!(logs[1].topics.len(), 1);
+        assert_eq!(logs[0].topics[0], H256::from_low_u64_be(0x42));
+        assert_eq!(logs[0].topics[1], H256::from_low_u64_be(0x69));
+        assert_eq!(logs[0].topics[2], H256::from_low_u64_be(0x69));
+        assert_eq!(logs[1].topics[0], H256::from_low_u64_be(0x77));
+        assert!(logs[0].data.is_empty());
+    }
+
+    #[test]
+    fn logs_record_emitter_and_data() {
+        let caller = H160::from_low_u64_be(0xC0);
+        let callee = H160::from_low_u64_be(0x1000);
+        let mut context = context_with_accounts(
+            // MSTORE(0, 0x2a), LOG1(31, 1, 1)
+            vec![(callee, contract("602a60005260016001601fa1"))],
+            ExecutionEnvironment {
+                contract_address: caller,
+                ..Default::default()
+            },
+        );
 
-        context.run(program).unwrap();
+        // CALL(GAS, 0x1000, 0, 0, 0, 0, 0) then LOG0(0, 0)
+        let result = context.run(decode("600060006000600060006110005af160006000a0"));
 
-        let logs = context.logs.borrow().to_owned();
-        assert_eq!(logs.len(), 2);
-        assert_eq!(logs[0].len(), 3);
-        assert_eq!(logs[1].len(), 1);
-        assert_eq!(logs[0][0], U256::from(0x42));
-        assert_eq!(logs[0][1], U256::from(0x69));
-        assert_eq!(logs[0][2], U256::from(0x69));
-        assert_eq!(logs[1][0], U256::from(0x77));
+        assert_eq!(
+            result.logs,
+            vec![
+                Log {
+                    address: callee,
+                    topics: vec![H256::from_low_u64_be(1)],
+                    data: vec![0x2a],
+                },
+                Log {
+                    address: caller,
+                    topics: Vec::new(),
+                    data: Vec::new(),
+                },
+            ]
+        );
     }
 
     #[test]
@@ -128,7 +281,7 @@ mod tests {
         ];
         let mut context = ExecutionContext::default();
 
-        assert!(context.run(program).is_ok());
+        assert!(context.run(program).is_success());
 
         let value = context.execution_machine.memory.read_bytes(0, 32);
 
@@ -157,7 +310,7 @@ mod tests {
         ];
         let mut context = ExecutionContext::default();
 
-        assert!(context.run(program).is_ok());
+        assert!(context.run(program).is_success());
         assert_eq!(
             context.execution_machine.stack.pop().unwrap(),
             U256::from(1)
@@ -171,7 +324,121 @@ mod tests {
         ];
         let mut context = ExecutionContext::default();
 
-        assert!(context.run(program).is_err());
+        assert!(context.run(program).is_halt());
+    }
+
+    #[test]
+    fn jump_into_push_data_fails() {
+        // PUSH1 4 JUMP PUSH1 0x5b STOP, 0x5b at offset 4 is PUSH data
+        assert!(ExecutionContext::default()
+            .run(decode("600456605b00"))
+            .is_halt());
+        // PUSH1 1 PUSH1 6 JUMPI PUSH1 0x5b STOP
+        assert!(ExecutionContext::default()
+            .run(decode("6001600657605b00"))
+            .is_halt());
+        // PUSH32 0xff..ff JUMP
+        assert!(ExecutionContext::default()
+            .run(decode(&format!("7f{}56", "ff".repeat(32))))
+            .is_halt());
+        // PUSH1 4 JUMP STOP JUMPDEST
+        assert!(ExecutionContext::default()
+            .run(decode("600456005b"))
+            .is_success());
+    }
+
+    #[test]
+    fn code_is_analyzed_once_per_hash() {
+        let program = decode("600456005b");
+        let mut context = ExecutionContext::default();
+        let global_env = context.global_env.clone();
+
+        assert!(context.run(program.clone()).is_success());
+        assert_eq!(global_env.jump_tables.len(), 1);
+
+        let mut context =
+            ExecutionContext::new(ExecutionEnvironment::default(), global_env.clone());
+        assert!(context.run(program).is_success());
+        assert_eq!(global_env.jump_tables.len(), 1);
+
+        let mut context =
+            ExecutionContext::new(ExecutionEnvironment::default(), global_env.clone());
+        assert!(context.run(decode("00")).is_success());
+        assert_eq!(global_env.jump_tables.len(), 2);
+    }
+
+    #[test]
+    fn identical_code_is_analyzed_once() {
+        let creator = H160::from_low_u64_be(0xC0);
+        let mut context = context_with_accounts(
+            vec![],
+            ExecutionEnvironment {
+                contract_address: creator,
+                ..Default::default()
+            },
+        );
+        let global_env = context.global_env.clone();
+
+        // MSTORE(0, PUSH22 INIT_CODE), CREATE(0, 10, 22) twice
+        let program = decode(&format!("75{INIT_CODE}6000526016600a6000f06016600a6000f0"));
+
+        assert!(context.run(program.clone()).is_success());
+        // the program, the init code and the runtime code
+        assert_eq!(global_env.jump_tables.len(), 3);
+
+        {
+            let global_storage = global_env.global_storage.borrow();
+            let first = global_storage[&create_address(creator, 0)].code.as_ref();
+            let second = global_storage[&create_address(creator, 1)].code.as_ref();
+            assert!(std::ptr::eq(
+                first.unwrap().jump_table(),
+                second.unwrap().jump_table()
+            ));
+        }
+
+        let mut context = ExecutionContext::new(
+            ExecutionEnvironment {
+                contract_address: creator,
+                ..Default::default()
+            },
+            global_env.clone(),
+        );
+        assert!(context.run(program).is_success());
+        assert_eq!(global_env.jump_tables.len(), 3);
+    }
+
+    #[test]
+    fn truncated_push_reads_zeros() {
+        // PUSH3 0x0102, the missing byte is read as zero
+        let mut context = ExecutionContext::default();
+
+        assert!(context.run(decode("620102")).is_success());
+        assert_eq!(
+            context.execution_machine.stack.pop().unwrap(),
+            U256::from(0x010200)
+        );
+    }
+
+    #[test]
+    fn code_copy_is_zero_padded() {
+        // PUSH1 8 PUSH1 2 PUSH1 0 CODECOPY
+        let mut context = ExecutionContext::default();
+
+        assert!(context.run(decode("6008600260003900")).is_success());
+        assert_eq!(
+            context.execution_machine.memory.read_bytes(0, 8),
+            vec![0x60, 0x02, 0x60, 0x00, 0x39, 0x00, 0x00, 0x00]
+        );
+
+        // an offset past the end of the code only copies zeros
+        let mut context = ExecutionContext::default();
+        assert!(context
+            .run(decode(&format!("60027f{}60003900", "ff".repeat(32))))
+            .is_success());
+        assert_eq!(
+            context.execution_machine.memory.read_bytes(0, 2),
+            vec![0, 0]
+        );
     }
 
     #[test]
@@ -182,11 +449,11 @@ mod tests {
         ];
         let mut context = ExecutionContext::default();
 
-        assert!(context.run(program).is_ok());
+        assert!(context.run(program).is_success());
         assert_eq!(
             context.execution_machine.stack.pop().unwrap(),
             U256::from_str_radix(
-                "17f4d2d1c0eeabac92fa5cea16f773d1dd884baa101e0ea7b89f8ef32c9c9f20",
+                "50e5f74212dad6a9c67b82fc788d26991d5803653e9ae5fcdbf14fa9b191598c",
                 16
             )
             .unwrap()
@@ -194,7 +461,7 @@ mod tests {
         assert_eq!(
             context.execution_machine.stack.pop().unwrap(),
             U256::from_str_radix(
-                "bafcb21c8036fea04eec31342b90d24355d75c520b7139d508e9a98641e1b0a8",
+                "a0c090b7c74257be051e946d72428e12a3d95bc8714766612bfd3b342319634d",
                 16
             )
             .unwrap()
@@ -208,9 +475,1654 @@ mod tests {
         ];
         let mut context = ExecutionContext::default();
 
-        assert!(context.run(program).is_ok(), "run failed");
+        assert!(context.run(program).is_success(), "run failed");
 
         let code_in_memory = context.execution_machine.memory.read_bytes(0, 4);
         assert_eq!(code_in_memory, vec![0x60, 0x00, 0x60, 0x23]);
     }
+
+    #[test]
+    fn gas_charged_per_operation() {
+        // PUSH1 0x01, PUSH1 0x00, MSTORE, PUSH1 32, PUSH1 0x00, SHA3, PUSH1 0x02, PUSH1 0x03, EXP
+        let program = vec![
+            0x60, 0x01, 0x60, 0x00, 0x52, 0x60, 32, 0x60, 0x00, 0x20, 0x60, 0x02, 0x60, 0x03, 0x0A,
+        ];
+        let mut context = ExecutionContext::default();
+
+        assert!(context.run(program).is_success());
+        // 3 * 6 (PUSH) + 3 + 3 (MSTORE + memory) + 30 + 6 (SHA3) + 10 + 50 (EXP)
+        assert_eq!(context.gas.used(), 120);
+    }
+
+    #[test]
+    fn gas_opcode_reports_remaining_gas() {
+        let program = vec![0x60, 0x00, 0x5A];
+        let mut context = ExecutionContext::new(
+            ExecutionEnvironment {
+                gas_limit: 100,
+                ..Default::default()
+            },
+            Rc::default(),
+        );
+
+        assert!(context.run(program).is_success());
+        assert_eq!(
+            context.execution_machine.stack.pop().unwrap(),
+            U256::from(100 - 3 - 2)
+        );
+    }
+
+    #[test]
+    fn memory_expansion_is_charged() {
+        // MSTORE at offset 0x400 needs 33 words of memory
+        let program = vec![0x60, 0x01, 0x61, 0x04, 0x00, 0x52];
+        let mut context = ExecutionContext::default();
+
+        assert!(context.run(program).is_success());
+        assert_eq!(context.execution_machine.memory.used_capacity(), 33 * 32);
+        assert_eq!(context.gas.used(), 3 + 3 + 3 + (33 * 3 + 33 * 33 / 512));
+    }
+
+    #[test]
+    fn out_of_gas_halts_and_consumes_all_gas() {
+        let program = vec![0x60, 0x01, 0x60, 0x02, 0x01, 0x60, 0x03, 0x01];
+        let mut context = ExecutionContext::new(
+            ExecutionEnvironment {
+                gas_limit: 10,
+                ..Default::default()
+            },
+            Rc::default(),
+        );
+
+        assert!(context.run(program).is_halt());
+        assert_eq!(context.gas.remaining(), 0);
+        assert_eq!(context.execution_machine.stack.height(), 1);
+    }
+
+    #[test]
+    fn huge_memory_offset_runs_out_of_gas() {
+        let program = vec![0x60, 0x01, 0x63, 0xFF, 0xFF, 0xFF, 0xFF, 0x52];
+        let mut context = ExecutionContext::default();
+
+        assert!(context.run(program).is_halt());
+        assert_eq!(context.execution_machine.memory.used_capacity(), 0);
+    }
+
+    #[test]
+    fn huge_memory_with_huge_gas_limit_runs_out_of_gas() {
+        // MSTORE(0xFFFFFFE0, 1), affordable with this much gas but not allocated
+        let program = decode("600163ffffffe052");
+        let mut context = ExecutionContext::new(
+            ExecutionEnvironment {
+                gas_limit: u64::MAX / 2,
+                ..Default::default()
+            },
+            Rc::default(),
+        );
+
+        let result = context.run(program);
+        assert_eq!(result.halt_reason(), Some(HaltReason::OutOfGas));
+        assert_eq!(context.execution_machine.memory.used_capacity(), 0);
+
+        // just under the limit is fine
+        let program = decode(&format!("600163{:08x}52", MAX_MEMORY_SIZE - 32));
+        assert!(ExecutionContext::new(
+            ExecutionEnvironment {
+                gas_limit: u64::MAX / 2,
+                ..Default::default()
+            },
+            Rc::default(),
+        )
+        .run(program)
+        .is_success());
+    }
+
+    #[test]
+    fn storage_operations() {
+        // SSTORE 0x69 at slot 1, then SLOAD slot 1 and slot 2
+        let program = vec![
+            0x60, 0x69, 0x60, 0x01, 0x55, 0x60, 0x01, 0x54, 0x60, 0x02, 0x54,
+        ];
+        let address = H160::from_low_u64_be(0x77);
+        let mut context = context_with_storage(address, BTreeMap::new(), 100_000);
+
+        assert!(context.run(program).is_success());
+        assert_eq!(context.execution_machine.stack.pop().unwrap(), U256::zero());
+        assert_eq!(
+            context.execution_machine.stack.pop().unwrap(),
+            U256::from(0x69)
+        );
+
+        let global_storage = context.global_env.global_storage.borrow();
+        let storage = &global_storage.get(&address).unwrap().storage;
+        assert_eq!(storage.get(&U256::one()), Some(&U256::from(0x69)));
+        assert_eq!(storage.len(), 1);
+    }
+
+    #[test]
+    fn storage_clear_is_refunded() {
+        // slot 0 starts at 1, SSTORE 0 then SSTORE 0 again
+        let program = vec![0x60, 0x00, 0x60, 0x00, 0x55, 0x60, 0x00, 0x60, 0x00, 0x55];
+        let address = H160::from_low_u64_be(0x77);
+        let storage = BTreeMap::from([(U256::zero(), U256::one())]);
+        let mut context = context_with_storage(address, storage, 100_000);
+
+        assert!(context.run(program).is_success());
+        assert_eq!(context.gas.refunded(), 4800);
+        assert_eq!(context.gas.used(), 3 * 4 + (2900 + 2100) + 100);
+        assert_eq!(
+            context.gas.final_refund(SpecId::Cancun),
+            context.gas.used() / 5
+        );
+        assert!(context.global_env.global_storage.borrow()[&address]
+            .storage
+            .is_empty());
+    }
+
+    #[test]
+    fn storage_restored_to_original_is_refunded() {
+        // slot 0 starts at 0, SSTORE 1 then SSTORE 0
+        let program = vec![0x60, 0x01, 0x60, 0x00, 0x55, 0x60, 0x00, 0x60, 0x00, 0x55];
+        let address = H160::from_low_u64_be(0x77);
+        let mut context = context_with_storage(address, BTreeMap::new(), 100_000);
+
+        assert!(context.run(program).is_success());
+        assert_eq!(context.gas.refunded(), 19900);
+    }
+
+    #[test]
+    fn sstore_fails_with_only_stipend_left() {
+        let program = vec![0x60, 0x01, 0x60, 0x00, 0x55];
+        let address = H160::from_low_u64_be(0x77);
+        let mut context = context_with_storage(address, BTreeMap::new(), 2306);
+
+        assert!(context.run(program).is_halt());
+        assert!(context.global_env.global_storage.borrow()[&address]
+            .storage
+            .is_empty());
+    }
+
+    #[test]
+    fn transactions_do_not_share_originals_logs_or_warm_slots() {
+        let mut context = ExecutionContext::default();
+
+        // SSTORE(0, 1), LOG0(0, 0)
+        let result = context.run(decode("600160005560006000a0"));
+        assert!(result.is_success());
+        assert_eq!(result.logs.len(), 1);
+
+        // SSTORE(0, 2), 1 is now the original value of the slot, which is cold again
+        let result = context.run(decode("6002600055"));
+        assert!(result.is_success());
+        assert_eq!(result.gas_used, 3 + 3 + 2100 + 2900);
+        assert!(result.logs.is_empty());
+        assert!(context.journal.borrow().entries().is_empty());
+        assert!(context.original_storage.borrow().is_empty());
+    }
+
+    #[test]
+    fn sstore_eip3529_vectors() {
+        // (code, original value, gas used, refund) from https://eips.ethereum.org/EIPS/eip-3529#test-cases
+        let vectors: [(&str, u64, u64, i64); 17] = [
+            ("60006000556000600055", 0, 212, 0),
+            ("60006000556001600055", 0, 20112, 0),
+            ("60016000556000600055", 0, 20112, 19900),
+            ("60016000556002600055", 0, 20112, 0),
+            ("60016000556001600055", 0, 20112, 0),
+            ("60006000556000600055", 1, 3012, 4800),
+            ("60006000556001600055", 1, 3012, 2800),
+            ("60006000556002600055", 1, 3012, 0),
+            ("60026000556000600055", 1, 3012, 4800),
+            ("60026000556003600055", 1, 3012, 0),
+            ("60026000556001600055", 1, 3012, 2800),
+            ("60026000556002600055", 1, 3012, 0),
+            ("60016000556000600055", 1, 3012, 4800),
+            ("60016000556002600055", 1, 3012, 0),
+            ("60016000556001600055", 1, 212, 0),
+            ("600160005560006000556001600055", 0, 40118, 19900),
+            ("600060005560016000556000600055", 1, 5918, 7600),
+        ];
+
+        let address = H160::from_low_u64_be(0x77);
+
+        for (code, original, used, refund) in vectors {
+            let program = decode(code);
+
+            let mut storage = BTreeMap::new();
+            if original != 0 {
+                storage.insert(U256::zero(), U256::from(original));
+            }
+
+            // the slot is warmed by an access list in the spec
+            let mut context = context_with_storage(address, storage, 100_000)
+                .with_access_list(vec![(address, vec![U256::zero()])]);
+
+            assert!(context.run(program).is_success(), "{code} failed");
+            assert_eq!(context.gas.used(), used, "gas used for {code}");
+            assert_eq!(context.gas.refunded(), refund, "refund for {code}");
+        }
+    }
+
+    #[test]
+    fn balance_of_cold_and_warm_accounts() {
+        // BALANCE(0x69), BALANCE(0x69), BALANCE(ADDRESS)
+        let program = vec![0x60, 0x69, 0x31, 0x60, 0x69, 0x31, 0x30, 0x31];
+        let mut context = ExecutionContext::default();
+
+        let result = context.run(program);
+        assert_eq!(context.gas.used(), 3 + 2600 + 3 + 100 + 2 + 100);
+        assert!(result
+            .access_set
+            .contains_address(&H160::from_low_u64_be(0x69)));
+    }
+
+    #[test]
+    fn selfbalance_is_the_balance_of_the_executing_account() {
+        let caller = H160::from_low_u64_be(0xCA);
+        let contract_address = H160::from_low_u64_be(0x1000);
+        let account = |balance: u64| AccountState {
+            balance: U256::from(balance),
+            ..Default::default()
+        };
+        let mut context = context_with_accounts(
+            vec![(caller, account(1)), (contract_address, account(2))],
+            ExecutionEnvironment {
+                caller,
+                contract_address,
+                ..Default::default()
+            },
+        );
+
+        // SELFBALANCE
+        assert!(context.run(vec![0x47]).is_success());
+        assert_eq!(
+            context.execution_machine.stack.pop().unwrap(),
+            U256::from(2)
+        );
+    }
+
+    #[test]
+    fn storage_access_is_recorded() {
+        let program = vec![0x60, 0x01, 0x54, 0x60, 0x69, 0x60, 0x02, 0x55];
+        let address = H160::from_low_u64_be(0x77);
+        let mut context = context_with_storage(address, BTreeMap::new(), 100_000);
+
+        let result = context.run(program);
+        let slots: Vec<_> = result.access_set.storage_keys().copied().collect();
+        assert_eq!(
+            slots,
+            vec![(address, U256::one()), (address, U256::from(2))]
+        );
+    }
+
+    #[test]
+    fn transient_storage_operations() {
+        // TSTORE 0x69 at slot 1, then TLOAD slot 1 and slot 2
+        let program = vec![
+            0x60, 0x69, 0x60, 0x01, 0x5D, 0x60, 0x01, 0x5C, 0x60, 0x02, 0x5C,
+        ];
+        let mut context = ExecutionContext::default();
+
+        assert!(context.run(program).is_success());
+        assert_eq!(context.execution_machine.stack.pop().unwrap(), U256::zero());
+        assert_eq!(
+            context.execution_machine.stack.pop().unwrap(),
+            U256::from(0x69)
+        );
+        assert_eq!(context.gas.used(), 3 * 4 + 100 * 3);
+
+        // discarded once the transaction is over
+        assert!(context.transient_storage.borrow().is_empty());
+        assert!(context.global_env.global_storage.borrow().is_empty());
+    }
+
+    #[test]
+    fn state_changes_fail_in_static_context() {
+        let programs = [
+            vec![0x60, 0x01, 0x60, 0x00, 0x5D],
+            vec![0x60, 0x01, 0x60, 0x00, 0x55],
+            vec![0x60, 0x00, 0x60, 0x00, 0xA0],
+            vec![0x60, 0x00, 0xFF],
+        ];
+
+        for program in programs {
+            let mut context = ExecutionContext::new(
+                ExecutionEnvironment {
+                    is_static: true,
+                    ..Default::default()
+                },
+                Rc::default(),
+            );
+
+            assert!(context.run(program).is_halt());
+            assert!(context.logs.borrow().is_empty());
+            assert!(context.global_env.global_storage.borrow().is_empty());
+        }
+    }
+
+    #[test]
+    fn call_copies_return_data() {
+        // callee returns 0x2077 as a word
+        let callee = H160::from_low_u64_be(0x1000);
+        let mut context = context_with_accounts(
+            vec![(callee, contract("61207760005260206000f3"))],
+            ExecutionEnvironment::default(),
+        );
+
+        // CALL(GAS, 0x1000, 0, 0, 0, 0, 32)
+        let program = decode("602060006000600060006110005af1");
+
+        assert!(context.run(program).is_success());
+        assert_eq!(context.execution_machine.stack.pop().unwrap(), U256::one());
+        assert_eq!(
+            U256::from_big_endian(&context.execution_machine.memory.read_bytes(0, 32)),
+            U256::from(0x2077)
+        );
+    }
+
+    #[test]
+    fn call_forwards_all_but_one_64th() {
+        // callee returns what GAS reports
+        let callee = H160::from_low_u64_be(0x1000);
+        let mut context = context_with_accounts(
+            vec![(callee, contract("5a60005260206000f3"))],
+            ExecutionEnvironment {
+                gas_limit: 100_000,
+                ..Default::default()
+            },
+        );
+
+        // CALL(NOT(0), 0x1000, 0, 0, 0, 0, 32)
+        let program = decode("60206000600060006000611000600019f1");
+
+        assert!(context.run(program).is_success());
+
+        let available = 100_000 - 24 - 3 - 2600;
+        let forwarded = available - available / 64;
+        assert_eq!(
+            U256::from_big_endian(&context.execution_machine.memory.read_bytes(0, 32)),
+            U256::from(forwarded - 2)
+        );
+    }
+
+    #[test]
+    fn call_transfers_value() {
+        let caller = H160::from_low_u64_be(0xC0);
+        let callee = H160::from_low_u64_be(0x2000);
+        let mut context = context_with_accounts(
+            vec![(
+                caller,
+                AccountState {
+                    balance: U256::from(100),
+                    ..Default::default()
+                },
+            )],
+            ExecutionEnvironment {
+                contract_address: caller,
+                ..Default::default()
+            },
+        );
+
+        // CALL(GAS, 0x2000, 10, 0, 0, 0, 0)
+        let program = decode("6000600060006000600a6120005af1");
+
+        assert!(context.run(program).is_success());
+        assert_eq!(context.execution_machine.stack.pop().unwrap(), U256::one());
+
+        let global_storage = context.global_env.global_storage.borrow();
+        assert_eq!(global_storage[&caller].balance, U256::from(90));
+        assert_eq!(global_storage[&callee].balance, U256::from(10));
+
+        // the unused stipend is given back to the caller
+        assert_eq!(context.gas.used(), 3 * 6 + 2 + 2600 + 9000 + 25000 - 2300);
+    }
+
+    #[test]
+    fn call_fails_with_insufficient_balance() {
+        // CALL(GAS, 0x2000, 10, 0, 0, 0, 0)
+        let program = decode("6000600060006000600a6120005af1");
+        let mut context = ExecutionContext::default();
+
+        assert!(context.run(program).is_success());
+        assert_eq!(context.execution_machine.stack.pop().unwrap(), U256::zero());
+        // the stipend is given back with the rest
+        assert_eq!(context.gas.used(), 3 * 6 + 2 + 2600 + 9000 + 25000 - 2300);
+        assert!(context.global_env.global_storage.borrow().is_empty());
+    }
+
+    #[test]
+    fn failed_value_transfer_returns_the_stipend() {
+        let address = H160::from_low_u64_be(0x1000);
+        let mut context = context_with_accounts(
+            vec![(
+                address,
+                AccountState {
+                    balance: U256::from(10),
+                    ..Default::default()
+                },
+            )],
+            ExecutionEnvironment {
+                contract_address: address,
+                gas_limit: 100_000,
+                ..Default::default()
+            },
+        );
+        context.depth = MAX_CALL_DEPTH;
+
+        // CALL(GAS, ADDRESS, 10, 0, 0, 0, 0) past the depth limit, then GAS
+        let program = decode("6000600060006000600a305af15a");
+
+        assert!(context.run(program).is_success());
+        let remaining = context.execution_machine.stack.pop().unwrap();
+        assert_eq!(context.execution_machine.stack.pop().unwrap(), U256::zero());
+        // warm ADDRESS and value transfer, with the stipend on top of what was forwarded
+        let charged = 3 * 5 + 2 + 2 + 100 + 9000 + 2;
+        assert_eq!(remaining, U256::from(100_000 - charged + 2300));
+    }
+
+    #[test]
+    fn zero_value_call_leaves_no_empty_account() {
+        let callee = H160::from_low_u64_be(0x1234);
+        // CALL(0xff, 0x1234, 0, 0, 0, 0, 0)
+        let program = decode("6000600060006000600061123460fff1");
+
+        let mut context = context_with_accounts(vec![], Default::default());
+        let result = context.run(program.clone());
+        assert!(result.is_success());
+        assert!(result.state_diff.get(&callee).is_none());
+        assert!(!context
+            .global_env
+            .global_storage
+            .borrow()
+            .contains_key(&callee));
+
+        // before EIP-161 the call creates the account
+        let mut context = context_with_spec(SpecId::Homestead, vec![], Default::default());
+        assert!(context.run(program).is_success());
+        assert!(context
+            .global_env
+            .global_storage
+            .borrow()
+            .contains_key(&callee));
+    }
+
+    #[test]
+    fn delegatecall_keeps_caller_and_storage() {
+        // callee stores CALLER at slot 0
+        let origin = H160::from_low_u64_be(0xEE);
+        let caller = H160::from_low_u64_be(0xC0);
+        let callee = H160::from_low_u64_be(0x1000);
+
+        for (opcode, owner, stored) in [("f1", callee, caller), ("f4", caller, origin)] {
+            let mut context = context_with_accounts(
+                vec![(callee, contract("33600055"))],
+                ExecutionEnvironment {
+                    caller: origin,
+                    contract_address: caller,
+                    ..Default::default()
+                },
+            );
+
+            let program = match opcode {
+                // CALL(GAS, 0x1000, 0, 0, 0, 0, 0)
+                "f1" => decode("600060006000600060006110005af1"),
+                // DELEGATECALL(GAS, 0x1000, 0, 0, 0, 0)
+                _ => decode("60006000600060006110005af4"),
+            };
+
+            assert!(context.run(program).is_success());
+            assert_eq!(context.execution_machine.stack.pop().unwrap(), U256::one());
+
+            let global_storage = context.global_env.global_storage.borrow();
+            assert_eq!(
+                global_storage[&owner].storage.get(&U256::zero()),
+                Some(&U256::from(stored.as_bytes()))
+            );
+        }
+    }
+
+    #[test]
+    fn staticcall_cannot_change_state() {
+        let callee = H160::from_low_u64_be(0x1000);
+        let mut context = context_with_accounts(
+            vec![(callee, contract("33600055"))],
+            ExecutionEnvironment::default(),
+        );
+
+        // STATICCALL(GAS, 0x1000, 0, 0, 0, 0)
+        let program = decode("60006000600060006110005afa");
+
+        assert!(context.run(program).is_success());
+        assert_eq!(context.execution_machine.stack.pop().unwrap(), U256::zero());
+        assert!(context.global_env.global_storage.borrow()[&callee]
+            .storage
+            .is_empty());
+    }
+
+    #[test]
+    fn call_depth_is_limited() {
+        // increments slot 0 then calls itself with all the gas it has
+        let address = H160::from_low_u64_be(0x1000);
+        let code = "60005460010160005560006000600060006000305af100";
+        let mut context = context_with_accounts(
+            vec![(address, contract(code))],
+            ExecutionEnvironment {
+                contract_address: address,
+                gas_limit: u64::MAX / 2,
+                ..Default::default()
+            },
+        );
+
+        assert!(context.run(decode(code)).is_success());
+        assert_eq!(
+            context.global_env.global_storage.borrow()[&address]
+                .storage
+                .get(&U256::zero()),
+            Some(&U256::from(1025))
+        );
+    }
+
+    // copies its runtime code `602a60005260206000f3` (returns 42 as a word) and returns it
+    const INIT_CODE: &str = "600a600c600039600a6000f3602a60005260206000f3";
+
+    #[test]
+    fn create_deploys_runtime_code() {
+        let creator = H160::from_low_u64_be(0xC0);
+        let mut context = context_with_accounts(
+            vec![],
+            ExecutionEnvironment {
+                contract_address: creator,
+                ..Default::default()
+            },
+        );
+
+        // MSTORE(0, PUSH22 INIT_CODE), CREATE(0, 10, 22)
+        let program = decode(&format!("75{INIT_CODE}6000526016600a6000f0"));
+
+        assert!(context.run(program).is_success());
+
+        let address = create_address(creator, 0);
+        assert_eq!(
+            context.execution_machine.stack.pop().unwrap(),
+            U256::from(address.as_bytes())
+        );
+
+        let global_storage = context.global_env.global_storage.borrow();
+        assert_eq!(global_storage[&creator].nonce, 1);
+        assert_eq!(global_storage[&address].nonce, 1);
+        assert_eq!(
+            global_storage[&address].code,
+            Some(decode("602a60005260206000f3").into())
+        );
+    }
+
+    #[test]
+    fn create2_deploys_at_salted_address_once() {
+        let creator = H160::from_low_u64_be(0xC0);
+        let mut context = context_with_accounts(
+            vec![],
+            ExecutionEnvironment {
+                contract_address: creator,
+                ..Default::default()
+            },
+        );
+
+        // MSTORE(0, PUSH22 INIT_CODE), CREATE2(0, 10, 22, 0x69) twice
+        let program = decode(&format!(
+            "75{INIT_CODE}60005260696016600a6000f560696016600a6000f5"
+        ));
+
+        assert!(context.run(program).is_success());
+
+        let address = create2_address(
+            creator,
+            H256::from_low_u64_be(0x69),
+            keccak256(decode(INIT_CODE)),
+        );
+        assert_eq!(context.execution_machine.stack.pop().unwrap(), U256::zero());
+        assert_eq!(
+            context.execution_machine.stack.pop().unwrap(),
+            U256::from(address.as_bytes())
+        );
+
+        let global_storage = context.global_env.global_storage.borrow();
+        assert_eq!(global_storage[&creator].nonce, 2);
+        assert!(global_storage[&address].code.is_some());
+    }
+
+    #[test]
+    fn create_transfers_value() {
+        let creator = H160::from_low_u64_be(0xC0);
+        let mut context = context_with_accounts(
+            vec![(
+                creator,
+                AccountState {
+                    balance: U256::from(100),
+                    ..Default::default()
+                },
+            )],
+            ExecutionEnvironment {
+                contract_address: creator,
+                ..Default::default()
+            },
+        );
+
+        // CREATE(10, 0, 0)
+        let program = decode("60006000600af0");
+
+        assert!(context.run(program).is_success());
+
+        let global_storage = context.global_env.global_storage.borrow();
+        assert_eq!(global_storage[&creator].balance, U256::from(90));
+        assert_eq!(
+            global_storage[&create_address(creator, 0)].balance,
+            U256::from(10)
+        );
+    }
+
+    #[test]
+    fn create_rejects_invalid_runtime_code() {
+        let programs = [
+            // init code returning 0xEF, see EIP-3541
+            "6c60ef60f81b60005260016000f3600052600d60136000f0",
+            // init code returning 24577 bytes, see EIP-170
+            "656160016000f36000526006601a6000f0",
+        ];
+
+        for program in programs {
+            let mut context = ExecutionContext::default();
+
+            assert!(context.run(decode(program)).is_success());
+            assert_eq!(context.execution_machine.stack.pop().unwrap(), U256::zero());
+
+            // all the gas given to the init code is gone
+            assert!(context.gas.remaining() < context.gas.limit() / 64);
+            assert!(!context
+                .global_env
+                .global_storage
+                .borrow()
+                .contains_key(&create_address(H160::zero(), 0)));
+        }
+    }
+
+    #[test]
+    fn create_fails_in_static_context() {
+        let program = decode("600060006000f0");
+        let mut context = ExecutionContext::new(
+            ExecutionEnvironment {
+                is_static: true,
+                ..Default::default()
+            },
+            Rc::default(),
+        );
+
+        assert!(context.run(program).is_halt());
+        assert!(context.global_env.global_storage.borrow().is_empty());
+    }
+
+    #[test]
+    fn revert_returns_data_and_discards_changes() {
+        // SSTORE(0, 1), MSTORE(0, 0x2a), REVERT(0, 32)
+        let program = decode("6001600055602a60005260206000fd");
+        let mut context = ExecutionContext::default();
+
+        let result = context.run(program);
+        assert!(result.is_revert());
+        assert_eq!(U256::from_big_endian(&result.data), U256::from(0x2a));
+        assert!(context.global_env.global_storage.borrow().is_empty());
+
+        // unlike an exceptional halt, the gas left is not consumed
+        assert_eq!(context.gas.used(), 3 * 6 + 22100 + 6);
+        assert_eq!(context.gas.refunded(), 0);
+    }
+
+    #[test]
+    fn reverted_call_rolls_back_callee_changes() {
+        let caller = H160::from_low_u64_be(0xC0);
+        let callee = H160::from_low_u64_be(0x1000);
+        let mut context = context_with_accounts(
+            vec![
+                (
+                    caller,
+                    AccountState {
+                        balance: U256::from(100),
+                        ..Default::default()
+                    },
+                ),
+                // SSTORE(0, 1), LOG0(0, 0), MSTORE(0, 0x2a), REVERT(0, 32)
+                (callee, contract("600160005560006000a0602a60005260206000fd")),
+            ],
+            ExecutionEnvironment {
+                contract_address: caller,
+                ..Default::default()
+            },
+        );
+
+        // CALL(GAS, 0x1000, 10, 0, 0, 0, 32)
+        let program = decode("6020600060006000600a6110005af1");
+
+        let result = context.run(program);
+        assert!(!result.is_revert());
+        assert_eq!(context.execution_machine.stack.pop().unwrap(), U256::zero());
+
+        // the revert data is still given to the caller
+        assert_eq!(
+            U256::from_big_endian(&context.execution_machine.memory.read_bytes(0, 32)),
+            U256::from(0x2a)
+        );
+
+        let global_storage = context.global_env.global_storage.borrow();
+        assert_eq!(global_storage[&caller].balance, U256::from(100));
+        assert_eq!(global_storage[&callee].balance, U256::zero());
+        assert!(global_storage[&callee].storage.is_empty());
+        assert!(context.logs.borrow().is_empty());
+        assert!(context.gas.remaining() > context.gas.limit() / 2);
+    }
+
+    #[test]
+    fn revert_discards_changes_of_successful_calls() {
+        let callee = H160::from_low_u64_be(0x1000);
+        let mut context = context_with_accounts(
+            // SSTORE(0, 1), STOP
+            vec![(callee, contract("600160005500"))],
+            ExecutionEnvironment::default(),
+        );
+
+        // CALL(GAS, 0x1000, 0, 0, 0, 0, 0), REVERT(0, 0)
+        let program = decode("600060006000600060006110005af160006000fd");
+
+        let result = context.run(program);
+        assert!(result.is_revert());
+        assert!(context.global_env.global_storage.borrow()[&callee]
+            .storage
+            .is_empty());
+        assert!(context.journal.borrow().entries().is_empty());
+
+        // the callee is cold again
+        assert!(!result.access_set.contains_address(&callee));
+    }
+
+    #[test]
+    fn reverted_create_keeps_sender_nonce() {
+        // init code REVERT(0, 0) stored in memory, then CREATE(0, 27, 5)
+        let program = decode("6460006000fd6000526005601b6000f0");
+        let mut context = ExecutionContext::default();
+
+        assert!(context.run(program).is_success());
+        assert_eq!(context.execution_machine.stack.pop().unwrap(), U256::zero());
+        assert!(context.gas.remaining() > context.gas.limit() / 2);
+
+        let global_storage = context.global_env.global_storage.borrow();
+        assert_eq!(global_storage[&H160::zero()].nonce, 1);
+        assert!(!global_storage.contains_key(&create_address(H160::zero(), 0)));
+    }
+
+    fn context_with_block(spec_id: SpecId) -> ExecutionContext {
+        ExecutionContext::new(
+            ExecutionEnvironment::default(),
+            Rc::new(GlobalEnvironment {
+                chain_id: 5,
+                spec_id,
+                current_block: CurrentBlockInformation {
+                    timestamp: 1_700_000_000,
+                    gas_limit: 30_000_000,
+                    block_number: 300,
+                    coinbase: H160::from_low_u64_be(0xC0FFEE),
+                    base_fee: U256::from(7),
+                    difficulty: U256::from(0x20000),
+                    prevrandao: H256::from_low_u64_be(0x1234),
+                    block_hashes: (0..300).rev().map(H256::from_low_u64_be).collect(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+        )
+    }
+
+    #[test]
+    fn block_information_operations() {
+        // COINBASE, TIMESTAMP, NUMBER, PREVRANDAO, GASLIMIT, CHAINID, BASEFEE
+        let program = decode("41424344454648");
+        let mut context = context_with_block(SpecId::Cancun);
+
+        assert!(context.run(program).is_success());
+        assert_eq!(context.gas.used(), 7 * 2);
+
+        let stack = &mut context.execution_machine.stack;
+        assert_eq!(stack.pop().unwrap(), U256::from(7));
+        assert_eq!(stack.pop().unwrap(), U256::from(5));
+        assert_eq!(stack.pop().unwrap(), U256::from(30_000_000));
+        assert_eq!(stack.pop().unwrap(), U256::from(0x1234));
+        assert_eq!(stack.pop().unwrap(), U256::from(300));
+        assert_eq!(stack.pop().unwrap(), U256::from(1_700_000_000));
+        assert_eq!(stack.pop().unwrap(), U256::from(0xC0FFEE));
+    }
+
+    #[test]
+    fn prevrandao_is_difficulty_before_merge() {
+        let mut context = context_with_block(SpecId::London);
+
+        assert!(context.run(vec![0x44]).is_success());
+        assert_eq!(
+            context.execution_machine.stack.pop().unwrap(),
+            U256::from(0x20000)
+        );
+    }
+
+    #[test]
+    fn blockhash_of_last_256_blocks_only() {
+        // the hash of block n is n
+        let cases = [
+            ("61012b40", 299),
+            ("602c40", 44),
+            ("602b40", 0),
+            ("61012c40", 0),
+            ("61ffff40", 0),
+        ];
+
+        for (program, expected) in cases {
+            let mut context = context_with_block(SpecId::Cancun);
+
+            assert!(context.run(decode(program)).is_success());
+            assert_eq!(
+                context.execution_machine.stack.pop().unwrap(),
+                U256::from(expected)
+            );
+        }
+    }
+
+    fn selfdestructing_context(spec_id: SpecId, code: &str) -> ExecutionContext {
+        let address = H160::from_low_u64_be(0xC0);
+
+        context_with_spec(
+            spec_id,
+            vec![(
+                address,
+                AccountState {
+                    balance: U256::from(100),
+                    ..contract(code)
+                },
+            )],
+            ExecutionEnvironment {
+                contract_address: address,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn selfdestruct_deletes_account_before_cancun() {
+        // SELFDESTRUCT(0x1000)
+        let code = "611000ff";
+        let mut context = selfdestructing_context(SpecId::Shanghai, code);
+
+        assert!(context.run(decode(code)).is_success());
+
+        let global_storage = context.global_env.global_storage.borrow();
+        assert!(!global_storage.contains_key(&H160::from_low_u64_be(0xC0)));
+        assert_eq!(
+            global_storage[&H160::from_low_u64_be(0x1000)].balance,
+            U256::from(100)
+        );
+
+        // cold beneficiary that did not exist, and no refund since London
+        assert_eq!(context.gas.used(), 3 + 5000 + 2600 + 25000);
+        assert_eq!(context.gas.refunded(), 0);
+    }
+
+    #[test]
+    fn selfdestruct_only_moves_balance_since_cancun() {
+        let code = "611000ff";
+        let mut context = selfdestructing_context(SpecId::Cancun, code);
+
+        assert!(context.run(decode(code)).is_success());
+
+        let global_storage = context.global_env.global_storage.borrow();
+        let account = &global_storage[&H160::from_low_u64_be(0xC0)];
+        assert_eq!(account.balance, U256::zero());
+        assert_eq!(account.code, Some(decode(code).into()));
+        assert_eq!(
+            global_storage[&H160::from_low_u64_be(0x1000)].balance,
+            U256::from(100)
+        );
+    }
+
+    #[test]
+    fn selfdestruct_to_itself_burns_balance_and_is_refunded_before_london() {
+        // SELFDESTRUCT(ADDRESS)
+        let code = "30ff";
+        let mut context = selfdestructing_context(SpecId::Berlin, code);
+
+        assert!(context.run(decode(code)).is_success());
+        assert!(context.global_env.global_storage.borrow().is_empty());
+        assert_eq!(context.gas.used(), 2 + 5000);
+        assert_eq!(context.gas.refunded(), 24000);
+
+        // the balance is kept when the account is not deleted
+        let mut context = selfdestructing_context(SpecId::Cancun, code);
+
+        assert!(context.run(decode(code)).is_success());
+        assert_eq!(
+            context.global_env.global_storage.borrow()[&H160::from_low_u64_be(0xC0)].balance,
+            U256::from(100)
+        );
+    }
+
+    #[test]
+    fn selfdestruct_deletes_contract_created_in_same_transaction() {
+        let beneficiary = H160::from_low_u64_be(0x1000);
+        let mut context = context_with_accounts(
+            vec![(
+                H160::zero(),
+                AccountState {
+                    balance: U256::from(5),
+                    ..Default::default()
+                },
+            )],
+            ExecutionEnvironment::default(),
+        );
+
+        // init code SELFDESTRUCT(0x1000) stored in memory, then CREATE(5, 28, 4)
+        let program = decode("63611000ff6000526004601c6005f0");
+
+        assert!(context.run(program).is_success());
+        assert_eq!(
+            context.execution_machine.stack.pop().unwrap(),
+            U256::from(create_address(H160::zero(), 0).as_bytes())
+        );
+
+        let global_storage = context.global_env.global_storage.borrow();
+        assert!(!global_storage.contains_key(&create_address(H160::zero(), 0)));
+        assert_eq!(global_storage[&beneficiary].balance, U256::from(5));
+        assert!(context.created_contracts.borrow().is_empty());
+    }
+
+    #[test]
+    fn call_to_identity_precompile() {
+        let mut context = ExecutionContext::default();
+
+        // MSTORE(0, 0x2a), STATICCALL(GAS, 0x04, 0, 32, 32, 32)
+        let program = decode("602a600052602060206020600060045afa");
+
+        assert!(context.run(program).is_success());
+        assert_eq!(context.execution_machine.stack.pop().unwrap(), U256::one());
+        assert_eq!(
+            U256::from_big_endian(&context.execution_machine.memory.read_bytes(32, 32)),
+            U256::from(0x2a)
+        );
+        // 18 for the identity itself, 100 for the warm access
+        assert_eq!(context.gas.limit() - context.gas.remaining(), 150);
+    }
+
+    #[test]
+    fn call_to_sha256_precompile() {
+        let mut context = ExecutionContext::default();
+
+        // CALL(GAS, 0x02, 0, 0, 0, 0, 32)
+        let program = decode("6020600060006000600060025af1");
+
+        assert!(context.run(program).is_success());
+        assert_eq!(context.execution_machine.stack.pop().unwrap(), U256::one());
+        assert_eq!(
+            context.execution_machine.memory.read_bytes(0, 32),
+            decode("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+        );
+    }
+
+    #[test]
+    fn failed_precompile_consumes_given_gas() {
+        let mut context = ExecutionContext::default();
+
+        // STATICCALL(10, 0x04, 0, 0, 0, 0), the identity needs at least 15
+        let program = decode("60006000600060006004600afa");
+
+        assert!(context.run(program).is_success());
+        assert_eq!(context.execution_machine.stack.pop().unwrap(), U256::zero());
+        assert_eq!(context.gas.limit() - context.gas.remaining(), 128);
+    }
+
+    #[test]
+    fn precompiles_depend_on_hardfork() {
+        // STATICCALL(GAS, 0x0a, 0, 0, 0, 0)
+        let program = "60006000600060006000600a5afa";
+
+        // an empty account before Cancun
+        let mut context =
+            context_with_spec(SpecId::Shanghai, vec![], ExecutionEnvironment::default());
+        assert!(context.run(decode(program)).is_success());
+        assert_eq!(context.execution_machine.stack.pop().unwrap(), U256::one());
+
+        // the point evaluation rejects an empty input afterwards
+        let mut context =
+            context_with_spec(SpecId::Cancun, vec![], ExecutionEnvironment::default());
+        assert!(context.run(decode(program)).is_success());
+        assert_eq!(context.execution_machine.stack.pop().unwrap(), U256::zero());
+    }
+
+    #[test]
+    fn return_data_of_last_call() {
+        let callee = H160::from_low_u64_be(0x1000);
+        let accounts = || {
+            // MSTORE(0, 0x2a), RETURN(0, 32)
+            vec![(callee, contract("602a60005260206000f3"))]
+        };
+        let mut context = context_with_accounts(accounts(), ExecutionEnvironment::default());
+
+        // CALL(GAS, 0x1000, 0, 0, 0, 0, 0), RETURNDATASIZE, RETURNDATACOPY(0, 0, 32)
+        let program = decode("600060006000600060006110005af13d6020600060003e");
+
+        assert!(context.run(program).is_success());
+        assert_eq!(
+            context.execution_machine.stack.pop().unwrap(),
+            U256::from(32)
+        );
+        assert_eq!(context.return_data.len(), 32);
+        assert_eq!(
+            U256::from_big_endian(&context.execution_machine.memory.read_bytes(0, 32)),
+            U256::from(0x2a)
+        );
+
+        // a call that can't be made clears the buffer, here one sending 1 wei it doesn't have
+        let mut context = context_with_accounts(accounts(), ExecutionEnvironment::default());
+        let program = decode(concat!(
+            "600060006000600060006110005af1",
+            "600060006000600060016110005af13d"
+        ));
+
+        assert!(context.run(program).is_success());
+        assert_eq!(context.execution_machine.stack.pop().unwrap(), U256::zero());
+        assert_eq!(context.execution_machine.stack.pop().unwrap(), U256::zero());
+        assert!(context.return_data.is_empty());
+    }
+
+    #[test]
+    fn return_data_copy_out_of_bounds_halts() {
+        let callee = H160::from_low_u64_be(0x1000);
+        let mut context = context_with_accounts(
+            vec![(callee, contract("602a60005260206000f3"))],
+            ExecutionEnvironment::default(),
+        );
+
+        // CALL(GAS, 0x1000, 0, 0, 0, 0, 0), RETURNDATACOPY(0, 1, 32)
+        let program = decode("600060006000600060006110005af16020600160003e");
+        assert!(context.run(program).is_halt());
+
+        // nothing to copy before any call
+        let mut context = ExecutionContext::default();
+        assert!(context.run(decode("6001600060003e")).is_halt());
+
+        // copying nothing is fine
+        let mut context = ExecutionContext::default();
+        assert!(context.run(decode("6000600060003e")).is_success());
+    }
+
+    #[test]
+    fn return_data_of_reverted_create() {
+        let mut context = ExecutionContext::default();
+
+        // init code MSTORE(0, 0x2a), REVERT(0, 32) stored in memory, then CREATE(0, 22, 10)
+        let program = decode("69602a60005260206000fd600052600a60166000f03d");
+
+        assert!(context.run(program).is_success());
+        assert_eq!(
+            context.execution_machine.stack.pop().unwrap(),
+            U256::from(32)
+        );
+        assert_eq!(context.execution_machine.stack.pop().unwrap(), U256::zero());
+        assert_eq!(
+            U256::from_big_endian(&context.return_data),
+            U256::from(0x2a)
+        );
+
+        // a successful one leaves nothing, the code being stored instead
+        let mut context = ExecutionContext::default();
+
+        // init code RETURN(0, 0) stored in memory, then CREATE(0, 27, 5)
+        let program = decode("6460006000f36000526005601b6000f03d");
+
+        assert!(context.run(program).is_success());
+        assert_eq!(context.execution_machine.stack.pop().unwrap(), U256::zero());
+        assert!(context.return_data.is_empty());
+    }
+
+    #[test]
+    fn external_code_operations() {
+        let code = "602a60005260206000f3";
+        let mut context = context_with_accounts(
+            vec![
+                (H160::from_low_u64_be(0x1000), contract(code)),
+                (
+                    H160::from_low_u64_be(0x2000),
+                    AccountState {
+                        balance: U256::one(),
+                        ..Default::default()
+                    },
+                ),
+            ],
+            ExecutionEnvironment::default(),
+        );
+
+        // EXTCODESIZE(0x1000), EXTCODECOPY(0x1000, 0, 7, 4),
+        // EXTCODEHASH(0x1000), EXTCODEHASH(0x2000), EXTCODEHASH(0x3000)
+        let program = decode(concat!(
+            "6110003b",
+            "6004600760006110003c",
+            "6110003f",
+            "6120003f",
+            "6130003f"
+        ));
+
+        assert!(context.run(program).is_success());
+
+        let mut pop = || context.execution_machine.stack.pop().unwrap();
+        assert_eq!(pop(), U256::zero());
+        assert_eq!(pop(), U256::from_big_endian(KECCAK_EMPTY.as_bytes()));
+        assert_eq!(
+            pop(),
+            U256::from_big_endian(keccak256(decode(code)).as_bytes())
+        );
+        assert_eq!(pop(), U256::from(10));
+
+        // copied past the end of the code
+        assert_eq!(
+            context.execution_machine.memory.read_bytes(0, 4),
+            vec![0x60, 0x00, 0xf3, 0x00]
+        );
+
+        // three cold accesses, two warm ones, 8 pushes, one word copied and one of memory
+        assert_eq!(
+            context.gas.limit() - context.gas.remaining(),
+            3 * 2600 + 2 * 100 + 8 * 3 + 3 + 3
+        );
+    }
+
+    #[test]
+    fn operations_depend_on_hardfork() {
+        let run = |spec, program| {
+            context_with_spec(spec, vec![], ExecutionEnvironment::default())
+                .run(decode(program))
+                .is_success()
+        };
+
+        // SHL(1, 1)
+        assert!(!run(SpecId::Byzantium, "600160011b"));
+        assert!(run(SpecId::Constantinople, "600160011b"));
+
+        // DELEGATECALL(0, 0, 0, 0, 0, 0), asking for all the gas left would run out of it
+        assert!(!run(SpecId::Frontier, "600060006000600060006000f4"));
+        assert!(run(SpecId::Homestead, "600060006000600060006000f4"));
+        assert!(!run(SpecId::Homestead, "6000600060006000600060005af4"));
+        assert!(run(
+            SpecId::TangerineWhistle,
+            "6000600060006000600060005af4"
+        ));
+
+        // TLOAD(0)
+        assert!(!run(SpecId::Shanghai, "60005c"));
+        assert!(run(SpecId::Cancun, "60005c"));
+
+        // PUSH0
+        assert!(!run(SpecId::Merge, "5f"));
+        assert!(run(SpecId::Shanghai, "5f"));
+    }
+
+    #[test]
+    fn every_halt_has_a_reason() {
+        let halt =
+            |mut context: ExecutionContext, program| context.run(decode(program)).halt_reason();
+        let default = || ExecutionContext::default();
+
+        assert_eq!(
+            halt(default(), "0c"),
+            Some(HaltReason::UndefinedOpcode(0x0c))
+        );
+        assert_eq!(halt(default(), "fe"), Some(HaltReason::InvalidOpcode));
+        assert_eq!(halt(default(), "600056"), Some(HaltReason::InvalidJump));
+        assert_eq!(halt(default(), "01"), Some(HaltReason::StackUnderflow));
+        assert_eq!(
+            halt(default(), "32"),
+            Some(HaltReason::NotSupported(OpCode::ORIGIN))
+        );
+        // RETURNDATACOPY(0, 0, 1) without any return data
+        assert_eq!(
+            halt(default(), "6001600060003e"),
+            Some(HaltReason::OutOfOffset)
+        );
+
+        let merge = context_with_spec(SpecId::Merge, vec![], ExecutionEnvironment::default());
+        assert_eq!(
+            halt(merge, "5f"),
+            Some(HaltReason::NotActivated(OpCode::PUSH(0)))
+        );
+
+        let starving = ExecutionContext::new(
+            ExecutionEnvironment {
+                gas_limit: 2,
+                ..Default::default()
+            },
+            Rc::default(),
+        );
+        assert_eq!(halt(starving, "6000"), Some(HaltReason::OutOfGas));
+
+        let read_only = ExecutionContext::new(
+            ExecutionEnvironment {
+                is_static: true,
+                ..Default::default()
+            },
+            Rc::default(),
+        );
+        assert_eq!(
+            halt(read_only, "6001600055"),
+            Some(HaltReason::StaticStateChange(OpCode::SSTORE))
+        );
+
+        let mut too_deep = default();
+        too_deep.depth = MAX_CALL_DEPTH + 1;
+        assert_eq!(halt(too_deep, "00"), Some(HaltReason::CallDepthExceeded));
+    }
+
+    #[test]
+    fn extreme_operands_do_not_panic() {
+        let mut context = ExecutionContext::default();
+        let program = [
+            // MOD(5, 0)
+            "6000600506",
+            // ADDMOD(MAX, 2, 3) and MULMOD(MAX, MAX, 12345) don't overflow
+            "600360027fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff08",
+            "6130397fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff09",
+            // BYTE(0, 0xab << 248) and BYTE(32, 1)
+            "7fab0000000000000000000000000000000000000000000000000000000000000060001a",
+            "600160201a",
+            // SHL(300, 1), SHR(MAX, MAX)
+            "600161012c1b",
+            "7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff1c",
+            // CALLDATALOAD(MAX)
+            "7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff35",
+        ]
+        .concat();
+        assert!(context.run(decode(&program)).is_success());
+
+        let mut results = Vec::new();
+        while let Ok(value) = context.execution_machine.stack.pop() {
+            results.push(value.as_u64());
+        }
+        results.reverse();
+
+        assert_eq!(results, vec![0, 2, 315, 0xab, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn random_programs_never_panic() {
+        // xorshift, so that a failure can be reproduced
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        let defined: Vec<u8> = (0..=255u8)
+            .filter(|opcode| OPCODES[*opcode as usize].is_some())
+            .collect();
+
+        for _ in 0..20_000 {
+            let mut program = Vec::new();
+
+            while program.len() < 96 {
+                // operands half of the time, so that operations don't all underflow
+                match next() % 8 {
+                    0 => program.push(0x5f),
+                    1 => program.extend([0x60, 0x01]),
+                    2 => program.extend([0x60, next() as u8]),
+                    3 => program.push(0x7f),
+                    _ => program.push(defined[next() as usize % defined.len()]),
+                }
+
+                if program.last() == Some(&0x7f) {
+                    program.extend([0xff; 32]);
+                }
+            }
+
+            // any outcome is fine, as long as it is not a panic
+            let _ = ExecutionContext::new(
+                ExecutionEnvironment {
+                    gas_limit: 100_000,
+                    calldata: vec![0x42; 40],
+                    ..Default::default()
+                },
+                Rc::default(),
+            )
+            .run(program);
+        }
+    }
+
+    #[test]
+    fn stack_is_validated_before_execution() {
+        // DUP1 and ADD on an empty stack
+        assert!(ExecutionContext::default().run(decode("80")).is_halt());
+        assert!(ExecutionContext::default().run(decode("600101")).is_halt());
+
+        // 1024 items then one more PUSH0
+        let mut context = ExecutionContext::default();
+        assert!(context.run(decode(&"5f".repeat(1024))).is_success());
+        assert!(ExecutionContext::default()
+            .run(decode(&"5f".repeat(1025)))
+            .is_halt());
+    }
+
+    #[test]
+    fn shift_operations() {
+        // SHL(1, 1) and SHR(1, 2)
+        let mut context = ExecutionContext::default();
+        assert!(context.run(decode("600160011b600260011c")).is_success());
+
+        assert_eq!(context.execution_machine.stack.pop().unwrap(), U256::one());
+        assert_eq!(
+            context.execution_machine.stack.pop().unwrap(),
+            U256::from(2)
+        );
+    }
+
+    #[test]
+    fn gas_schedule_depends_on_hardfork() {
+        let gas_used = |spec, program| {
+            let mut context = context_with_spec(spec, vec![], ExecutionEnvironment::default());
+            assert!(context.run(decode(program)).is_success());
+            context.gas.used()
+        };
+
+        // SSTORE(0, 1), SLOAD(0), the slot is warm after the SSTORE since Berlin
+        let program = "600160005560005450";
+        assert_eq!(gas_used(SpecId::Frontier, program), 3 * 3 + 20000 + 50 + 2);
+        assert_eq!(
+            gas_used(SpecId::Petersburg, program),
+            3 * 3 + 20000 + 200 + 2
+        );
+        assert_eq!(gas_used(SpecId::Istanbul, program), 3 * 3 + 20000 + 800 + 2);
+        assert_eq!(
+            gas_used(SpecId::Cancun, program),
+            3 * 3 + 20000 + 2100 + 100 + 2
+        );
+
+        // BALANCE(0x1000)
+        let program = "6110003150";
+        assert_eq!(gas_used(SpecId::Homestead, program), 3 + 20 + 2);
+        assert_eq!(gas_used(SpecId::Byzantium, program), 3 + 400 + 2);
+        assert_eq!(gas_used(SpecId::Istanbul, program), 3 + 700 + 2);
+        assert_eq!(gas_used(SpecId::Berlin, program), 3 + 2600 + 2);
+    }
+
+    #[test]
+    fn coinbase_is_warm_since_shanghai() {
+        // BALANCE(0xC0FFEE)
+        let program = "62c0ffee3150";
+
+        let mut context = context_with_block(SpecId::Merge);
+        assert!(context.run(decode(program)).is_success());
+        assert_eq!(context.gas.used(), 3 + 2600 + 2);
+
+        let mut context = context_with_block(SpecId::Shanghai);
+        assert!(context.run(decode(program)).is_success());
+        assert_eq!(context.gas.used(), 3 + 100 + 2);
+    }
+
+    #[test]
+    fn each_transaction_is_pre_warmed_with_its_own_access_list() {
+        // BALANCE(0x69), BALANCE(0xC0FFEE), SLOAD(1)
+        let program = decode("60693162c0ffee31600154");
+        let mut context = context_with_block(SpecId::Shanghai);
+
+        context.access_list = vec![(H160::from_low_u64_be(0x69), vec![])];
+        let result = context.run(program.clone());
+        assert!(result.is_success());
+        assert_eq!(result.gas_used, 3 + 100 + 3 + 100 + 3 + 2100);
+
+        // only the slot is listed now, the coinbase is still warm
+        context.access_list = vec![(H160::zero(), vec![U256::one()])];
+        let result = context.run(program);
+        assert!(result.is_success());
+        assert_eq!(result.gas_used, 3 + 2600 + 3 + 100 + 3 + 100);
+    }
+
+    #[test]
+    fn refund_rules_depend_on_hardfork() {
+        let storage = || {
+            vec![(
+                H160::zero(),
+                AccountState {
+                    storage: BTreeMap::from([(U256::zero(), U256::one())]),
+                    ..Default::default()
+                },
+            )]
+        };
+
+        // SSTORE(0, 0) clearing the slot
+        let program = "6000600055";
+
+        let mut context = context_with_spec(SpecId::Berlin, storage(), Default::default());
+        assert!(context.run(decode(program)).is_success());
+        assert_eq!(context.gas.refunded(), 15000);
+        assert_eq!(context.gas.final_refund(SpecId::Berlin), 5006 / 2);
+
+        let mut context = context_with_spec(SpecId::London, storage(), Default::default());
+        assert!(context.run(decode(program)).is_success());
+        assert_eq!(context.gas.refunded(), 4800);
+        assert_eq!(context.gas.final_refund(SpecId::London), 5006 / 5);
+    }
+
+    #[test]
+    fn result_reports_gas_logs_and_state_changes() {
+        // slot 0 starts at 1, SSTORE(0, 0), SSTORE(1, 2) and LOG0(0, 0)
+        let program = decode("6000600055600260015560006000a0");
+        let address = H160::from_low_u64_be(0x77);
+        let storage = BTreeMap::from([(U256::zero(), U256::one())]);
+        let mut context = context_with_storage(address, storage, 100_000);
+
+        let result = context.run(program);
+
+        assert_eq!(result.status, ExecutionStatus::Success);
+        assert_eq!(result.logs.len(), 1);
+        assert!(result.gas_refunded > 0);
+        assert_eq!(
+            result.gas_refunded,
+            context.gas.final_refund(SpecId::Cancun)
+        );
+        assert_eq!(result.gas_used, context.gas.used() - result.gas_refunded);
+
+        let changes = &result.state_diff.get(&address).unwrap().storage;
+        assert_eq!(changes.len(), 2);
+        assert_eq!(
+            changes[&U256::zero()],
+            Diff {
+                from: U256::one(),
+                to: U256::zero()
+            }
+        );
+        assert_eq!(
+            changes[&U256::one()],
+            Diff {
+                from: U256::zero(),
+                to: U256::from(2)
+            }
+        );
+    }
+
+    #[test]
+    fn failed_results_change_nothing() {
+        // SSTORE(0, 1) and LOG0(0, 0), then REVERT(0, 0)
+        let mut context = ExecutionContext::default();
+        let result = context.run(decode("600160005560006000a060006000fd"));
+
+        assert!(result.is_revert());
+        assert!(result.state_diff.is_empty());
+        assert!(result.logs.is_empty());
+        assert!(result.gas_used < context.gas.limit());
+
+        // the same ending with INVALID
+        let mut context = ExecutionContext::default();
+        let result = context.run(decode("600160005560006000a0fe"));
+
+        assert_eq!(result.halt_reason(), Some(HaltReason::InvalidOpcode));
+        assert!(result.state_diff.is_empty());
+        assert!(result.logs.is_empty());
+        assert_eq!(result.gas_used, context.gas.limit());
+        assert_eq!(result.gas_refunded, 0);
+    }
+
+    #[test]
+    fn deploy_reports_created_address() {
+        let address = H160::from_low_u64_be(0xc0de);
+        let execution_env = ExecutionEnvironment {
+            contract_address: address,
+            ..Default::default()
+        };
+
+        // MSTORE(0, 0x2a) and RETURN(31, 1), the runtime code is 0x2a
+        let mut context = context_with_accounts(vec![], execution_env.clone());
+        let result = context.deploy(decode("602a6000526001601ff3"));
+
+        assert!(result.is_success());
+        assert_eq!(result.created_address, Some(address));
+
+        let account = result.state_diff.get(&address).unwrap();
+        assert_eq!(account.code.as_ref().unwrap().to.bytes(), &[0x2a]);
+        assert_eq!(account.nonce, Some(Diff { from: 0, to: 1 }));
+        assert_eq!(
+            context.global_env.global_storage.borrow()[&address].code,
+            Some(vec![0x2a].into())
+        );
+
+        // runtime code starting with 0xEF is rejected
+        let mut context = context_with_accounts(vec![], execution_env);
+        let result = context.deploy(decode("60ef6000526001601ff3"));
+
+        assert_eq!(result.halt_reason(), Some(HaltReason::InvalidCodePrefix));
+        assert_eq!(result.created_address, None);
+        assert!(!context
+            .global_env
+            .global_storage
+            .borrow()
+            .contains_key(&address));
+    }
+
+    /// Writes down everything it is told about
+    #[derive(Default)]
+    struct Recorder {
+        events: Vec<String>,
+    }
+
+    impl Inspector for Recorder {
+        fn step(&mut self, ctx: &ExecutionContext, opcode: OpCode) {
+            self.events.push(format!(
+                "{} {opcode} stack {} gas {}",
+                ctx.execution_machine.pc.get(),
+                ctx.execution_machine.stack.height(),
+                ctx.gas.remaining()
+            ));
+        }
+
+        fn step_end(&mut self, _ctx: &ExecutionContext, opcode: OpCode, halt: Option<HaltReason>) {
+            if let Some(reason) = halt {
+                self.events.push(format!("{opcode} halted: {reason}"));
+            }
+        }
+
+        fn call(&mut self, kind: OpCode, code_address: H160, env: &ExecutionEnvironment) {
+            self.events
+                .push(format!("{kind} {code_address:?} from {:?}", env.caller));
+        }
+
+        fn call_end(&mut self, end: &FrameEnd) {
+            self.events.push(format!("call end {:?}", end.status));
+        }
+
+        fn create(&mut self, kind: OpCode, init_code: &[u8], env: &ExecutionEnvironment) {
+            self.events.push(format!(
+                "{kind} {} bytes at {:?}",
+                init_code.len(),
+                env.contract_address
+            ));
+        }
+
+        fn create_end(&mut self, end: &FrameEnd) {
+            self.events.push(format!("create end {:?}", end.status));
+        }
+
+        fn log(&mut self, log: &Log) {
+            self.events.push(format!("log from {:?}", log.address));
+        }
+
+        fn selfdestruct(&mut self, address: H160, beneficiary: H160, balance: U256) {
+            self.events.push(format!(
+                "selfdestruct {address:?} to {beneficiary:?} {balance}"
+            ));
+        }
+    }
+
+    #[test]
+    fn inspector_sees_every_step() {
+        let recorder = Rc::new(RefCell::new(Recorder::default()));
+        let mut evm = Evm::new_with_config(EvmConfig::default());
+        evm.set_inspector(recorder.clone());
+
+        // PUSH1 1, PUSH1 2, ADD then an undefined operation
+        assert!(evm.build_executor().run(decode("60016002010c")).is_halt());
+
+        let limit = ExecutionEnvironment::default().gas_limit;
+        assert_eq!(
+            recorder.borrow().events,
+            vec![
+                format!("0 PUSH1 stack 0 gas {limit}"),
+                format!("2 PUSH1 stack 1 gas {}", limit - 3),
+                format!("4 ADD stack 2 gas {}", limit - 6),
+                format!("5 UNDEFINED(0x0c) stack 1 gas {}", limit - 9),
+                "UNDEFINED(0x0c) halted: undefined operation 0x0c".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn inspector_sees_calls_creates_logs_and_selfdestructs() {
+        let callee = H160::from_low_u64_be(0x1000);
+        let caller = H160::from_low_u64_be(0xC0);
+        let recorder = Rc::new(RefCell::new(Recorder::default()));
+        let mut context = context_with_accounts(
+            // LOG0(0, 0), SELFDESTRUCT(0xbe)
+            vec![(callee, contract("60006000a060beff"))],
+            ExecutionEnvironment {
+                contract_address: caller,
+                ..Default::default()
+            },
+        )
+        .with_inspector(recorder.clone());
+
+        // CALL(GAS, 0x1000, 0, 0, 0, 0, 0) then CREATE(0, 0, 0)
+        let program = decode("600060006000600060006110005af1600060006000f0");
+        assert!(context.run(program).is_success());
+
+        let created = create_address(caller, 0);
+        let events: Vec<_> = recorder
+            .borrow()
+            .events
+            .iter()
+            .filter(|event| !event.contains(" stack "))
+            .cloned()
+            .collect();
+
+        assert_eq!(
+            events,
+            vec![
+                format!("CALL {callee:?} from {caller:?}"),
+                format!("log from {callee:?}"),
+                format!(
+                    "selfdestruct {callee:?} to {:?} 0",
+                    H160::from_low_u64_be(0xbe)
+                ),
+                "call end Success".to_string(),
+                format!("CREATE 0 bytes at {created:?}"),
+                "create end Success".to_string(),
+            ]
+        );
+    }
 }